@@ -0,0 +1,226 @@
+use crate::error::{ensure, Error};
+use crate::exec::{CallContext, TagInfo};
+use crate::hash::merkle::{MerkleHash, MerkleNode, MerkleProof, Pairing};
+use crate::hash::Sha256;
+use crate::Executor;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::{U128, U64};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{env, near_bindgen, serde_json, AccountId, Balance};
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::ExecutorContract;
+
+/// The hash that joins the audit log's nodes.
+const AUDIT_HASH: MerkleHash = MerkleHash::Sha256;
+
+/// Record of a finished execution, appended into the audit log.
+///
+/// The log's leaf for this entry is the `sha256` of its borsh
+/// serialization. Each entry is also logged as json when it's
+/// appended, so it can be stored off-chain and later proven.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AuditEntry {
+    /// The position of this entry on the log, starting at `0`.
+    pub seq: U64,
+    pub app_id: String,
+    pub action_id: U64,
+    pub user_id: AccountId,
+    pub contract_id: AccountId,
+    pub method_name: String,
+    /// The `sha256` of the call arguments.
+    pub args_hash: Sha256,
+    /// The forwarded deposit, in yoctoNear.
+    pub deposit: U128,
+    /// The block timestamp, in nanoseconds, of when the execution
+    /// started.
+    pub timestamp: U64,
+    /// Whether the contract call succeeded.
+    pub success: bool,
+}
+
+impl AuditEntry {
+    /// The `sha256` of the borsh serialization of this entry.
+    pub fn leaf(&self) -> Sha256 {
+        let bytes = self.try_to_vec().unwrap();
+        Sha256::hash_bytes(&bytes)
+    }
+}
+
+/// The part of an [`AuditEntry`] that is known when the execution
+/// starts, forwarded into the callback that records the entry.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AuditCall {
+    pub contract_id: AccountId,
+    pub method_name: String,
+    pub args_hash: Sha256,
+    pub deposit: U128,
+    pub timestamp: U64,
+}
+
+impl AuditCall {
+    pub fn new(context: &CallContext, deposit: Balance) -> Self {
+        Self {
+            contract_id: context.contract_call.contract_id.clone(),
+            method_name: context.contract_call.method_name.clone(),
+            args_hash: Sha256::hash_bytes(context.contract_call.args.as_bytes()),
+            deposit: deposit.into(),
+            timestamp: env::block_timestamp().into(),
+        }
+    }
+}
+
+#[near_bindgen]
+impl Executor {
+    /// Gets how many entries the audit log has.
+    pub fn get_audit_len(&self) -> U64 {
+        self.audit_leaves.len().into()
+    }
+
+    /// Gets the current audit log root, ie. the root of the Merkle
+    /// tree of every entry.
+    ///
+    /// With no entries, this is the zeroed node.
+    pub fn get_audit_root(&self) -> MerkleNode {
+        self.audit_root(self.audit_leaves.len())
+    }
+
+    /// Gets the audit log root right after the entry `seq` was
+    /// appended.
+    pub fn get_audit_root_at(&self, seq: U64) -> MerkleNode {
+        ensure(seq.0 < self.audit_leaves.len(), Error::MissingAuditEntry);
+        self.audit_root(seq.0 + 1)
+    }
+
+    /// Gets the proof that the entry `seq` is included in the root
+    /// right after the entry `to_seq` was appended (defaults to the
+    /// latest entry).
+    ///
+    /// The proof has one sibling per level of the tree, and only
+    /// covers the first `2^32` entries.
+    ///
+    /// See [`crate::hash::merkle::verify_proof()`].
+    pub fn get_audit_proof(&self, seq: U64, to_seq: Option<U64>) -> MerkleProof {
+        let len = self.audit_leaves.len();
+        let to_seq = to_seq.map(|s| s.0).unwrap_or_else(|| len.saturating_sub(1));
+        ensure(
+            seq.0 <= to_seq && to_seq < len && to_seq <= u32::MAX as u64,
+            Error::MissingAuditEntry,
+        );
+
+        let size = to_seq + 1;
+        let height = audit_height(size);
+        let zeroed = zeroed_nodes(height);
+        let siblings = (0..height)
+            .map(|level| self.audit_node(level, (seq.0 >> level) ^ 1, size, &zeroed))
+            .collect();
+        MerkleProof {
+            hash: AUDIT_HASH,
+            pairing: Pairing::Indexed(seq.0 as u32),
+            siblings,
+        }
+    }
+}
+
+/// The height of the audit tree with `size` entries, which is padded
+/// with zeroed leaves up to the next power of two.
+fn audit_height(size: u64) -> u8 {
+    match size {
+        0 | 1 => 0,
+        size => (64 - (size - 1).leading_zeros()) as u8,
+    }
+}
+
+/// The roots of the subtrees that only have zeroed leaves, by their
+/// level, from `0` up to `height`.
+///
+/// Each is the pair of the previous one, so this takes one hash per
+/// level.
+fn zeroed_nodes(height: u8) -> Vec<MerkleNode> {
+    let mut nodes = vec![MerkleNode::default()];
+    for level in 0..height as usize {
+        let node = AUDIT_HASH.hash_pair(&nodes[level], &nodes[level]);
+        nodes.push(node);
+    }
+    nodes
+}
+
+impl Executor {
+    /// Appends the finished execution into the audit log.
+    pub(crate) fn record_audit_entry(
+        &mut self,
+        tag_info: &TagInfo,
+        call: AuditCall,
+        success: bool,
+    ) {
+        let entry = AuditEntry {
+            seq: self.audit_leaves.len().into(),
+            app_id: tag_info.app_id.clone(),
+            action_id: tag_info.action_id,
+            user_id: tag_info.user_id.clone(),
+            contract_id: call.contract_id,
+            method_name: call.method_name,
+            args_hash: call.args_hash,
+            deposit: call.deposit,
+            timestamp: call.timestamp,
+            success,
+        };
+        self.audit_leaves.push(&entry.leaf());
+
+        // stores the root of each subtree that the new leaf completes
+        let (mut level, mut index) = (0, entry.seq.0);
+        while index & 1 == 1 {
+            let left = self.audit_stored_node(level, index - 1);
+            let right = self.audit_stored_node(level, index);
+            let parent = AUDIT_HASH.hash_pair(&left, &right);
+            level += 1;
+            index >>= 1;
+            self.audit_nodes.insert(&(level, index), &Sha256(parent.0));
+        }
+
+        env::log_str(&serde_json::to_string(&entry).unwrap());
+    }
+
+    /// The root of the audit tree with the first `size` entries.
+    fn audit_root(&self, size: u64) -> MerkleNode {
+        match size {
+            0 => MerkleNode::default(),
+            size => {
+                let height = audit_height(size);
+                self.audit_node(height, 0, size, &zeroed_nodes(height))
+            }
+        }
+    }
+
+    /// The node at `index` of `level` (`0` for the leaves) on the
+    /// audit tree with the first `size` entries, where `zeroed` are the
+    /// [`zeroed_nodes()`] up to at least `level`.
+    ///
+    /// Only the nodes on the right edge of the tree are not stored,
+    /// so this takes at most one hash per level.
+    fn audit_node(&self, level: u8, index: u64, size: u64, zeroed: &[MerkleNode]) -> MerkleNode {
+        let first = (index as u128) << level;
+        let end = (index as u128 + 1) << level;
+        if end <= size as u128 {
+            self.audit_stored_node(level, index)
+        } else if first >= size as u128 {
+            zeroed[level as usize].clone()
+        } else {
+            let left = self.audit_node(level - 1, index * 2, size, zeroed);
+            let right = self.audit_node(level - 1, index * 2 + 1, size, zeroed);
+            AUDIT_HASH.hash_pair(&left, &right)
+        }
+    }
+
+    /// The root of a complete subtree, which is stored as soon as its
+    /// last leaf is appended.
+    fn audit_stored_node(&self, level: u8, index: u64) -> MerkleNode {
+        let node = match level {
+            0 => self.audit_leaves.get(index),
+            level => self.audit_nodes.get(&(level, index)),
+        };
+        node.unwrap().into()
+    }
+}
@@ -1,11 +1,24 @@
+use crate::error::Error;
 use crate::Executor;
 use near_sdk::near_bindgen;
 
 #[cfg(not(target_arch = "wasm32"))]
 use crate::ExecutorContract;
 
+pub mod algorithm;
+pub mod der;
 pub mod ecdsa_secp256k1;
+pub mod ecdsa_secp256r1;
 pub mod eddsa_ed25519;
+pub mod encoding;
+pub mod envelope;
+pub mod eth;
+#[cfg(all(feature = "host_crypto", target_arch = "wasm32"))]
+pub mod host;
+pub mod nep413;
+pub mod schnorr_secp256k1;
+pub mod threshold;
+pub mod webauthn;
 
 pub use ecdsa_secp256k1::types::PubKeyUncompressedNoHeader as EcdsaSecp256k1PublicKey;
 pub use ecdsa_secp256k1::types::SignRecoverable as EcdsaSecp256k1Signature;
@@ -13,18 +26,23 @@ pub use ecdsa_secp256k1::types::SignRecoverable as EcdsaSecp256k1Signature;
 pub use eddsa_ed25519::types::PubKey as EddsaEd25519PublicKey;
 pub use eddsa_ed25519::types::Sign as EddsaEd25519Signature;
 
+use ecdsa_secp256k1::types::VerifyMode;
+
 #[near_bindgen]
 impl Executor {
     // TODO: write comments
     //
     // signature verification that is compatible to Near
+    //
+    // the `mode` is as in `verify_msg()`.
     pub fn verify_hashed_msg(
         sign: Bs58EncodedSignature,
         pubkey: NearEncodedPubkey,
         msg_hash: crate::hash::Sha256,
+        mode: Option<VerifyMode>,
     ) -> bool {
         let pubkey = pubkey.parse();
-        Self::verify_inner(sign, pubkey, msg_hash)
+        Self::verify_inner(sign, pubkey, msg_hash, mode.unwrap_or_default())
     }
 
     /// Verifies if `pubkey` matches `sign` with the `sha256` hash of
@@ -33,7 +51,16 @@ impl Executor {
     /// Note: Internally the hashed msg is hashed again by the
     /// signature verification algorithm. This is compatible with
     /// Near's behavior.
-    pub fn verify_msg(sign: Bs58EncodedSignature, pubkey: NearEncodedPubkey, msg: String) -> bool {
+    ///
+    /// The `mode` is for `Secp256k1` signatures, and defaults to
+    /// [`VerifyMode::Lenient`], which also accepts the higher-S form,
+    /// as Near does.
+    pub fn verify_msg(
+        sign: Bs58EncodedSignature,
+        pubkey: NearEncodedPubkey,
+        msg: String,
+        mode: Option<VerifyMode>,
+    ) -> bool {
         let msg_hash = {
             use digest::Digest;
             let mut sha2_hash = sha2::Sha256::new();
@@ -45,7 +72,58 @@ impl Executor {
             crate::hash::Sha256(arr)
         };
         let pubkey = pubkey.parse();
-        Self::verify_inner(sign, pubkey, msg_hash)
+        Self::verify_inner(sign, pubkey, msg_hash, mode.unwrap_or_default())
+    }
+
+    /// Verifies many signatures at once, as
+    /// [`try_verify_msg()`](Self::try_verify_msg) would for each
+    /// entry.
+    ///
+    /// Returns whether each entry matched, in the same order.
+    /// Malformed entries are reported as not matching.
+    ///
+    /// Each entry is verified on its own, so that the results are the
+    /// same as for individual calls. A single batch of the `Ed25519`
+    /// entries would be cofactored, and so it could accept entries
+    /// that [`eddsa_ed25519_verify()`](Self::eddsa_ed25519_verify)
+    /// rejects.
+    pub fn verify_batch(entries: Vec<SignedMsg>) -> Vec<bool> {
+        entries
+            .into_iter()
+            .map(|entry| Self::try_verify_msg(entry.sign, entry.pubkey, entry.msg, None).valid)
+            .collect()
+    }
+
+    /// Same as [`verify_msg()`](Self::verify_msg), but malformed
+    /// inputs are reported by the `error` instead of failing.
+    pub fn try_verify_msg(
+        sign: Bs58EncodedSignature,
+        pubkey: NearEncodedPubkey,
+        msg: String,
+        mode: Option<VerifyMode>,
+    ) -> Verification {
+        let msg_hash = crate::hash::Sha256::hash_bytes(msg.as_bytes());
+        let mode = mode.unwrap_or_default();
+        pubkey
+            .try_parse()
+            .and_then(|pubkey| Self::try_verify_inner(sign, pubkey, msg_hash, mode))
+            .into()
+    }
+
+    /// Same as [`verify_hashed_msg()`](Self::verify_hashed_msg), but
+    /// malformed inputs are reported by the `error` instead of
+    /// failing.
+    pub fn try_verify_hashed_msg(
+        sign: Bs58EncodedSignature,
+        pubkey: NearEncodedPubkey,
+        msg_hash: crate::hash::Sha256,
+        mode: Option<VerifyMode>,
+    ) -> Verification {
+        let mode = mode.unwrap_or_default();
+        pubkey
+            .try_parse()
+            .and_then(|pubkey| Self::try_verify_inner(sign, pubkey, msg_hash, mode))
+            .into()
     }
 }
 
@@ -53,46 +131,157 @@ impl Executor {
     /// Note: Internally the hashed msg is hashed again by the
     /// signature verification algorithm. This is compatible with
     /// Near's behavior.
+    ///
+    /// Fails on malformed inputs, except that non-canonical
+    /// signatures are only reported as not matching.
+    ///
+    /// The `mode` is only for `Secp256k1` signatures.
     pub fn verify_inner(
         sign: Bs58EncodedSignature,
         pubkey: near_sdk::PublicKey,
         msg_hash: crate::hash::Sha256,
+        mode: VerifyMode,
     ) -> bool {
+        Self::verify_decoded_inner(&sign.decode(), pubkey, msg_hash, mode)
+    }
+
+    /// Same as [`verify_inner()`](Self::verify_inner), but with the
+    /// `sign` already decoded.
+    pub fn verify_decoded_inner(
+        sign: &[u8],
+        pubkey: near_sdk::PublicKey,
+        msg_hash: crate::hash::Sha256,
+        mode: VerifyMode,
+    ) -> bool {
+        match Self::try_verify_decoded_inner(sign, pubkey, msg_hash, mode) {
+            Ok(is_match) => is_match,
+            Err(Error::NonCanonicalSignature) => false,
+            Err(e) => e.panic(),
+        }
+    }
+
+    /// Returns whether `pubkey` matches `sign` with the `msg_hash`,
+    /// or the reason why the inputs are malformed.
+    ///
+    /// Note: Internally the hashed msg is hashed again by the
+    /// signature verification algorithm. This is compatible with
+    /// Near's behavior.
+    pub fn try_verify_inner(
+        sign: Bs58EncodedSignature,
+        pubkey: near_sdk::PublicKey,
+        msg_hash: crate::hash::Sha256,
+        mode: VerifyMode,
+    ) -> Result<bool, Error> {
+        Self::try_verify_decoded_inner(&sign.try_decode()?, pubkey, msg_hash, mode)
+    }
+
+    /// Same as [`try_verify_inner()`](Self::try_verify_inner), but
+    /// with the `sign` already decoded.
+    ///
+    /// In the [`VerifyMode::Strict`] mode, a `Secp256k1` signature in
+    /// the higher-S form is a [`Error::NonCanonicalSignature`].
+    pub fn try_verify_decoded_inner(
+        sign: &[u8],
+        pubkey: near_sdk::PublicKey,
+        msg_hash: crate::hash::Sha256,
+        mode: VerifyMode,
+    ) -> Result<bool, Error> {
         use near_sdk::CurveType;
-        let sign = sign.decode();
+        use std::convert::TryFrom;
         match (pubkey.curve_type(), sign.len()) {
             (CurveType::ED25519, 64) => {
                 let mut sign_raw = [0; 64];
                 sign_raw.copy_from_slice(&sign[0..64]);
                 let sign = EddsaEd25519Signature(sign_raw);
+                ed25519_dalek::Signature::from_bytes(&sign.0)
+                    .map_err(|_| Error::NonCanonicalSignature)?;
 
-                let pubkey: EddsaEd25519PublicKey = pubkey.into();
+                let pubkey = EddsaEd25519PublicKey::try_from(pubkey)?;
+                ed25519_dalek::PublicKey::from_bytes(&pubkey.0).map_err(|_| Error::InvalidPoint)?;
 
                 // note: msg_hash will be hashed again internally, this is
                 // compatible with Near's behavior.
-                Executor::eddsa_ed25519_verify(pubkey, sign, &msg_hash.0)
+                Ok(Executor::eddsa_ed25519_verify(pubkey, sign, &msg_hash.0))
             }
-            (CurveType::SECP256K1, 65) => {
-                let mut sign_raw = [0; 65];
-                sign_raw.copy_from_slice(&sign[0..65]);
-                let sign = EcdsaSecp256k1Signature(sign_raw);
+            // with or without the recovery id, which isn't needed
+            (CurveType::SECP256K1, 65) | (CurveType::SECP256K1, 64) => {
+                let mut sign: k256::ecdsa::Signature = {
+                    use k256::ecdsa::signature::Signature;
+                    if sign.len() == 65 {
+                        k256::ecdsa::recoverable::Signature::from_bytes(sign)
+                            .map_err(|_| Error::NonCanonicalSignature)?
+                            .into()
+                    } else {
+                        k256::ecdsa::Signature::from_bytes(sign)
+                            .map_err(|_| Error::NonCanonicalSignature)?
+                    }
+                };
+                match mode {
+                    VerifyMode::Strict if bool::from(sign.s().is_high()) => {
+                        return Err(Error::NonCanonicalSignature)
+                    }
+                    VerifyMode::Strict => {}
+                    // the verification itself only accepts the lower-S form
+                    VerifyMode::Lenient => {
+                        sign.normalize_s()
+                            .map_err(|_| Error::NonCanonicalSignature)?;
+                    }
+                }
                 let sign: ecdsa_secp256k1::types::SignCompact = sign.into();
 
-                let pubkey: EcdsaSecp256k1PublicKey = pubkey.into();
+                let pubkey = EcdsaSecp256k1PublicKey::try_from(pubkey)?;
                 let pubkey: ecdsa_secp256k1::types::PubKeyUncompressed = pubkey.into();
+                k256::PublicKey::from_sec1_bytes(&pubkey.0).map_err(|_| Error::InvalidPoint)?;
 
                 // note: msg_hash will be hashed again internally, this is
                 // compatible with Near's behavior.
-                Executor::ecdsa_secp256k1_verify_uncompressed_msg_bytes(pubkey, sign, &msg_hash.0)
+                Ok(Executor::ecdsa_secp256k1_verify_uncompressed_msg_bytes(
+                    pubkey,
+                    sign,
+                    &msg_hash.0,
+                ))
             }
-            (curve_type, sign_len) => panic!(
-                "Wrong sign length of {} for the curve type {:?}",
-                sign_len, curve_type
-            ),
+            _ => Err(Error::WrongSignatureLength),
         }
     }
 }
 
+/// Result of a [`Executor::try_verify_msg()`] or of a
+/// [`Executor::try_verify_hashed_msg()`].
+#[derive(near_sdk::serde::Serialize, near_sdk::serde::Deserialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Verification {
+    /// Whether the `pubkey` matched the `sign`.  
+    /// Always `false` if there is an `error`.
+    pub valid: bool,
+    /// Why the inputs are malformed, if they are.
+    pub error: Option<Error>,
+}
+
+impl From<Result<bool, Error>> for Verification {
+    fn from(res: Result<bool, Error>) -> Self {
+        match res {
+            Ok(valid) => Self { valid, error: None },
+            Err(error) => Self {
+                valid: false,
+                error: Some(error),
+            },
+        }
+    }
+}
+
+/// A message, its signature and the public key that supposedly
+/// signed it.
+///
+/// See [`Executor::verify_msg()`] for each field.
+#[derive(near_sdk::serde::Serialize, near_sdk::serde::Deserialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SignedMsg {
+    pub sign: Bs58EncodedSignature,
+    pub pubkey: NearEncodedPubkey,
+    pub msg: String,
+}
+
 #[derive(
     near_sdk::serde::Serialize, near_sdk::serde::Deserialize, Clone, PartialEq, Debug, Default,
 )]
@@ -102,7 +291,58 @@ pub struct NearEncodedPubkey(pub String);
 
 impl NearEncodedPubkey {
     pub fn parse(&self) -> near_sdk::PublicKey {
-        self.0.parse().unwrap()
+        self.try_parse().unwrap_or_else(|e| e.panic())
+    }
+
+    /// Parses the pubkey, which has an optional `{header}:` prefix
+    /// with the curve, followed by the key in any
+    /// [`Encoding`](encoding::Encoding).
+    ///
+    /// Secp256k1 keys can be in the 64-byte form without the header,
+    /// as Near has them, or in the SEC1 compressed (33-byte) or
+    /// uncompressed (65-byte) forms.  
+    /// On a missing prefix, the curve is detected by the size of the
+    /// key.
+    pub fn try_parse(&self) -> Result<near_sdk::PublicKey, Error> {
+        use ecdsa_secp256k1::types::{PubKeyCompressed, PubKeyUncompressed};
+        use near_sdk::CurveType;
+        use std::convert::{TryFrom, TryInto};
+
+        let (curve_type, data) = match self.0.split_once(':') {
+            Some(("ed25519", data)) => (Some(CurveType::ED25519), data),
+            Some(("secp256k1", data)) => (Some(CurveType::SECP256K1), data),
+            Some((tag, _)) if encoding::Encoding::from_tag(tag).is_none() => {
+                return Err(Error::UnknownCurve)
+            }
+            _ => (None, self.0.as_str()),
+        };
+        let data = encoding::decode(data)?;
+        let curve_type = curve_type.unwrap_or(match data.len() {
+            33 | 64 | 65 => CurveType::SECP256K1,
+            // as on Near, ed25519 is the default
+            _ => CurveType::ED25519,
+        });
+
+        // normalizes secp256k1 keys into the form without the header
+        let data = match (curve_type, data.len()) {
+            (CurveType::SECP256K1, 33) => {
+                let pubkey = PubKeyCompressed(data.try_into().unwrap());
+                let pubkey =
+                    EcdsaSecp256k1PublicKey::try_from(pubkey).map_err(|_| Error::InvalidPoint)?;
+                pubkey.0.to_vec()
+            }
+            (CurveType::SECP256K1, 65) => {
+                let pubkey = PubKeyUncompressed(data.try_into().unwrap());
+                let pubkey =
+                    EcdsaSecp256k1PublicKey::try_from(pubkey).map_err(|_| Error::InvalidPoint)?;
+                pubkey.0.to_vec()
+            }
+            _ => data,
+        };
+
+        let mut bytes = vec![curve_type as u8];
+        bytes.extend(data);
+        near_sdk::PublicKey::try_from(bytes).map_err(|_| Error::WrongPubkeyLength)
     }
 }
 
@@ -112,6 +352,15 @@ impl From<String> for NearEncodedPubkey {
     }
 }
 
+impl From<EcdsaSecp256k1PublicKey> for NearEncodedPubkey {
+    fn from(pubkey: EcdsaSecp256k1PublicKey) -> Self {
+        Self(format!(
+            "secp256k1:{}",
+            near_sdk::bs58::encode(&pubkey.0).into_string()
+        ))
+    }
+}
+
 #[derive(
     near_sdk::serde::Serialize, near_sdk::serde::Deserialize, Clone, PartialEq, Debug, Default,
 )]
@@ -121,7 +370,12 @@ pub struct Bs58EncodedSignature(pub String);
 
 impl Bs58EncodedSignature {
     pub fn decode(&self) -> Vec<u8> {
-        near_sdk::bs58::decode(&self.0).into_vec().unwrap()
+        self.try_decode().unwrap_or_else(|e| e.panic())
+    }
+    /// Decodes the signature, which is base58 unless it's in another
+    /// [`Encoding`](encoding::Encoding).
+    pub fn try_decode(&self) -> Result<Vec<u8>, Error> {
+        encoding::decode(&self.0)
     }
     pub fn encode(bytes: &[u8]) -> Self {
         Self(near_sdk::bs58::encode(bytes).into_string())
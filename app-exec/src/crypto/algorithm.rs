@@ -0,0 +1,232 @@
+//! Verification of messages signed with an explicit algorithm.
+//!
+//! Unlike with [`Executor::verify_msg()`], the algorithm is not
+//! inferred from the curve of the public key and from the size of the
+//! signature, so eg. `Ed25519` and `Ed25519ph` signatures can't be
+//! mistaken for each other.
+
+use super::{
+    ecdsa_secp256k1, Bs58EncodedSignature, EcdsaSecp256k1PublicKey, EddsaEd25519PublicKey,
+    NearEncodedPubkey, Verification,
+};
+use crate::error::Error;
+use crate::{hash, Executor};
+use near_sdk::near_bindgen;
+use near_sdk::serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::ExecutorContract;
+
+/// The algorithm that created a signature.
+///
+/// As on Near, what gets signed is the `sha256` hash of the message,
+/// which each algorithm then handles in its own way.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(tag = "name", rename_all = "snake_case")]
+pub enum Algorithm {
+    /// `Ed25519`, the same as Near's.
+    Ed25519,
+    /// `Ed25519ph`, which signs the `sha512` of the hash of the
+    /// message, with an optional `context` of at most 255 bytes.
+    Ed25519ph { context: Option<String> },
+    /// `Ed25519ctx`, which binds the signature to a `context` of
+    /// between 1 and 255 bytes.
+    Ed25519ctx { context: String },
+    /// Ecdsa on secp256k1, with the signature in the 64-byte compact
+    /// form. The hash of the message is hashed again with `sha256`.
+    ///
+    /// The signature must be in the lower-S form, as with
+    /// [`VerifyMode::Strict`](super::ecdsa_secp256k1::types::VerifyMode::Strict).
+    EcdsaSecp256k1,
+    /// The same as [`Self::EcdsaSecp256k1`], but with the recovery id
+    /// after the signature, which must recover the public key.
+    EcdsaSecp256k1Recoverable,
+}
+
+#[near_bindgen]
+impl Executor {
+    /// Verifies if `pubkey` matches `sign` with the `sha256` hash of
+    /// the `msg`, as signed by the `algorithm`.
+    pub fn verify_tagged_msg(
+        algorithm: Algorithm,
+        sign: Bs58EncodedSignature,
+        pubkey: NearEncodedPubkey,
+        msg: String,
+    ) -> bool {
+        let msg_hash = hash::Sha256::hash_bytes(msg.as_bytes());
+        Self::verify_tagged_inner(&algorithm, sign, pubkey.parse(), msg_hash)
+    }
+
+    /// Verifies if `pubkey` matches `sign` with the `msg_hash`, as
+    /// signed by the `algorithm`.
+    pub fn verify_tagged_hashed_msg(
+        algorithm: Algorithm,
+        sign: Bs58EncodedSignature,
+        pubkey: NearEncodedPubkey,
+        msg_hash: hash::Sha256,
+    ) -> bool {
+        Self::verify_tagged_inner(&algorithm, sign, pubkey.parse(), msg_hash)
+    }
+
+    /// Same as [`verify_tagged_msg()`](Self::verify_tagged_msg), but
+    /// malformed inputs are reported by the `error` instead of
+    /// failing.
+    pub fn try_verify_tagged_msg(
+        algorithm: Algorithm,
+        sign: Bs58EncodedSignature,
+        pubkey: NearEncodedPubkey,
+        msg: String,
+    ) -> Verification {
+        let msg_hash = hash::Sha256::hash_bytes(msg.as_bytes());
+        pubkey
+            .try_parse()
+            .and_then(|pubkey| Self::try_verify_tagged_inner(&algorithm, sign, pubkey, msg_hash))
+            .into()
+    }
+
+    /// Same as
+    /// [`verify_tagged_hashed_msg()`](Self::verify_tagged_hashed_msg),
+    /// but malformed inputs are reported by the `error` instead of
+    /// failing.
+    pub fn try_verify_tagged_hashed_msg(
+        algorithm: Algorithm,
+        sign: Bs58EncodedSignature,
+        pubkey: NearEncodedPubkey,
+        msg_hash: hash::Sha256,
+    ) -> Verification {
+        pubkey
+            .try_parse()
+            .and_then(|pubkey| Self::try_verify_tagged_inner(&algorithm, sign, pubkey, msg_hash))
+            .into()
+    }
+}
+
+impl Executor {
+    /// Fails on malformed inputs, except that non-canonical
+    /// signatures are only reported as not matching.
+    pub fn verify_tagged_inner(
+        algorithm: &Algorithm,
+        sign: Bs58EncodedSignature,
+        pubkey: near_sdk::PublicKey,
+        msg_hash: hash::Sha256,
+    ) -> bool {
+        match Self::try_verify_tagged_inner(algorithm, sign, pubkey, msg_hash) {
+            Ok(is_match) => is_match,
+            Err(Error::NonCanonicalSignature) => false,
+            Err(e) => e.panic(),
+        }
+    }
+
+    /// Returns whether `pubkey` matches `sign` with the `msg_hash`,
+    /// as signed by the `algorithm`, or the reason why the inputs are
+    /// malformed.
+    pub fn try_verify_tagged_inner(
+        algorithm: &Algorithm,
+        sign: Bs58EncodedSignature,
+        pubkey: near_sdk::PublicKey,
+        msg_hash: hash::Sha256,
+    ) -> Result<bool, Error> {
+        use near_sdk::CurveType;
+        let sign = sign.try_decode()?;
+        match (algorithm, pubkey.curve_type()) {
+            (Algorithm::Ed25519, CurveType::ED25519) => {
+                let (pubkey, sign) = ed25519_inputs(pubkey, &sign)?;
+                Ok(Executor::eddsa_ed25519_verify(
+                    pubkey.into(),
+                    sign.into(),
+                    &msg_hash.0,
+                ))
+            }
+            (Algorithm::Ed25519ph { context }, CurveType::ED25519) => {
+                let context = context.as_ref().map(|s| s.as_bytes());
+                if context.map_or(false, |c| c.len() > 255) {
+                    return Err(Error::BadContext);
+                }
+                let (pubkey, sign) = ed25519_inputs(pubkey, &sign)?;
+                let prehashed = hash::Sha512::hash_bytes(&msg_hash.0);
+                Ok(pubkey.verify_prehashed(prehashed, context, &sign).is_ok())
+            }
+            (Algorithm::Ed25519ctx { context }, CurveType::ED25519) => {
+                if context.is_empty() || context.len() > 255 {
+                    return Err(Error::BadContext);
+                }
+                let (pubkey, sign) = ed25519_inputs(pubkey, &sign)?;
+                Ok(Executor::eddsa_ed25519_verify_ctx(
+                    pubkey.into(),
+                    sign.into(),
+                    &msg_hash.0,
+                    context.as_bytes(),
+                ))
+            }
+            (Algorithm::EcdsaSecp256k1, CurveType::SECP256K1) => {
+                if sign.len() != 64 {
+                    return Err(Error::WrongSignatureLength);
+                }
+                let sign = {
+                    use k256::ecdsa::signature::Signature;
+                    k256::ecdsa::Signature::from_bytes(&sign)
+                        .map_err(|_| Error::NonCanonicalSignature)?
+                };
+                // the verification only accepts the lower-S form
+                if bool::from(sign.s().is_high()) {
+                    return Err(Error::NonCanonicalSignature);
+                }
+
+                let pubkey = EcdsaSecp256k1PublicKey::try_from(pubkey)?;
+                let pubkey: ecdsa_secp256k1::types::PubKeyUncompressed = pubkey.into();
+                k256::PublicKey::from_sec1_bytes(&pubkey.0).map_err(|_| Error::InvalidPoint)?;
+
+                Ok(Executor::ecdsa_secp256k1_verify_uncompressed_msg_bytes(
+                    pubkey,
+                    sign.into(),
+                    &msg_hash.0,
+                ))
+            }
+            (Algorithm::EcdsaSecp256k1Recoverable, CurveType::SECP256K1) => {
+                if sign.len() != 65 {
+                    return Err(Error::WrongSignatureLength);
+                }
+                let recoverable = {
+                    use k256::ecdsa::signature::Signature;
+                    k256::ecdsa::recoverable::Signature::from_bytes(&sign)
+                        .map_err(|_| Error::NonCanonicalSignature)?
+                };
+                let without_recovery_id: k256::ecdsa::Signature = recoverable.into();
+                if bool::from(without_recovery_id.s().is_high()) {
+                    return Err(Error::NonCanonicalSignature);
+                }
+                let sign: ecdsa_secp256k1::types::SignRecoverable = recoverable.into();
+
+                // the hash of the message is hashed again, as it is
+                // when signing
+                let digest = hash::Sha256::hash_bytes(&msg_hash.0);
+                let recovered = Executor::ecdsa_secp256k1_recover_pubkey(&sign, &digest.0);
+                let pubkey = EcdsaSecp256k1PublicKey::try_from(pubkey)?;
+                Ok(recovered.map_or(false, |recovered| {
+                    EcdsaSecp256k1PublicKey::from(recovered) == pubkey
+                }))
+            }
+            _ => Err(Error::WrongAlgorithm),
+        }
+    }
+}
+
+/// Parses the `pubkey` as a point and the `sign` as a canonical
+/// signature, as `ed25519` expects.
+fn ed25519_inputs(
+    pubkey: near_sdk::PublicKey,
+    sign: &[u8],
+) -> Result<(ed25519_dalek::PublicKey, ed25519_dalek::Signature), Error> {
+    if sign.len() != 64 {
+        return Err(Error::WrongSignatureLength);
+    }
+    let sign =
+        ed25519_dalek::Signature::from_bytes(sign).map_err(|_| Error::NonCanonicalSignature)?;
+
+    let pubkey = EddsaEd25519PublicKey::try_from(pubkey)?;
+    let pubkey =
+        ed25519_dalek::PublicKey::from_bytes(&pubkey.0).map_err(|_| Error::InvalidPoint)?;
+    Ok((pubkey, sign))
+}
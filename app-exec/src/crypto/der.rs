@@ -0,0 +1,88 @@
+//! The DER encoding of ecdsa signatures, as used by WebAuthn, X.509
+//! and Bitcoin.
+//!
+//! The signature is a `SEQUENCE` of two `INTEGER`s, `r` and `s`:
+//!
+//! ```text
+//! 0x30 len 0x02 len(r) r 0x02 len(s) s
+//! ```
+
+const SEQUENCE: u8 = 0x30;
+const INTEGER: u8 = 0x02;
+
+/// Parses a DER encoded signature into the compact form, ie. `r`
+/// followed by `s`, each as 32-bytes big-endian.
+///
+/// Only the strict DER encoding is accepted: the lengths must be
+/// exact and the integers must be positive and minimally encoded.
+/// Returns `None` otherwise.
+pub fn parse_ecdsa_signature(der: &[u8]) -> Option<[u8; 64]> {
+    let (tag, len, rest) = (*der.get(0)?, *der.get(1)?, der.get(2..)?);
+    // the signature is never long enough to need a long-form length
+    if tag != SEQUENCE || len as usize != rest.len() || len >= 0x80 {
+        return None;
+    }
+    let (r, rest) = parse_integer(rest)?;
+    let (s, rest) = parse_integer(rest)?;
+    if !rest.is_empty() {
+        return None;
+    }
+
+    let mut res = [0; 64];
+    res[32 - r.len()..32].copy_from_slice(r);
+    res[64 - s.len()..].copy_from_slice(s);
+    Some(res)
+}
+
+/// Encodes a compact signature, ie. `r` followed by `s`, each as
+/// 32-bytes big-endian, into DER.
+pub fn encode_ecdsa_signature(sign: &[u8; 64]) -> Vec<u8> {
+    let r = encode_integer(&sign[..32]);
+    let s = encode_integer(&sign[32..]);
+    let mut res = vec![SEQUENCE, (r.len() + s.len()) as u8];
+    res.extend(r);
+    res.extend(s);
+    res
+}
+
+/// Returns the big-endian bytes of the integer, without the sign
+/// padding, and what comes after it.
+fn parse_integer(der: &[u8]) -> Option<(&[u8], &[u8])> {
+    let (tag, len) = (*der.get(0)?, *der.get(1)? as usize);
+    if tag != INTEGER || len == 0 {
+        return None;
+    }
+    let value = der.get(2..2 + len)?;
+    let rest = &der[2 + len..];
+
+    // negative
+    if value[0] & 0x80 != 0 {
+        return None;
+    }
+    // a leading zero is only allowed to keep the value positive
+    let value = match value {
+        [0, next, ..] if next & 0x80 != 0 => &value[1..],
+        [0, _, ..] => return None,
+        _ => value,
+    };
+    if value.len() > 32 {
+        return None;
+    }
+    Some((value, rest))
+}
+
+fn encode_integer(value: &[u8]) -> Vec<u8> {
+    let first_nonzero = value
+        .iter()
+        .position(|b| *b != 0)
+        .unwrap_or(value.len() - 1);
+    let value = &value[first_nonzero..];
+    let mut res = vec![INTEGER, value.len() as u8];
+    // keeps it positive
+    if value[0] & 0x80 != 0 {
+        res[1] += 1;
+        res.push(0);
+    }
+    res.extend(value);
+    res
+}
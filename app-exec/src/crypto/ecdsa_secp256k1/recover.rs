@@ -0,0 +1,80 @@
+use super::types;
+use crate::error::{Error, OrPanicStr};
+use crate::{hash, Executor};
+use near_sdk::near_bindgen;
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::ExecutorContract;
+
+#[near_bindgen]
+impl Executor {
+    /// Recovers the public key that created the recoverable `sign`
+    /// of the `sha256` hash of the `msg`.
+    ///
+    /// Note that any valid signature recovers to some public key, so
+    /// the result must still be compared against the expected key.
+    pub fn ecdsa_secp256k1_recover(
+        sign: types::SignRecoverable,
+        msg: String,
+    ) -> types::RecoveredPubKey {
+        let msg_hash = hash::Sha256::hash_bytes(msg.as_bytes());
+        Self::ecdsa_secp256k1_recover_prehashed(sign, msg_hash)
+    }
+
+    /// Recovers the public key that created the recoverable `sign`
+    /// of the `msg_hash`.
+    ///
+    /// The `msg_hash` must be the result of a `sha256` of the msg,
+    /// and must have a total size of 32-bytes.
+    ///
+    /// Note that any valid signature recovers to some public key, so
+    /// the result must still be compared against the expected key.
+    pub fn ecdsa_secp256k1_recover_prehashed(
+        sign: types::SignRecoverable,
+        msg_hash: hash::Sha256,
+    ) -> types::RecoveredPubKey {
+        let pubkey = Self::ecdsa_secp256k1_recover_pubkey(&sign, &msg_hash.0)
+            .or_panic_str(Error::RecoverFailed);
+        pubkey.into()
+    }
+}
+
+impl Executor {
+    /// Recovers the public key that created the recoverable `sign`
+    /// of the `msg_hash`.
+    ///
+    /// The `msg_hash` can be the result of any 32-bytes hash, such as
+    /// `sha256` or `keccak256`.
+    ///
+    /// Returns `None` if the signature is malformed, or if it
+    /// recovers to the identity point, which is not a public key.
+    pub fn ecdsa_secp256k1_recover_pubkey(
+        sign: &types::SignRecoverable,
+        msg_hash: &[u8; 32],
+    ) -> Option<k256::PublicKey> {
+        use k256::elliptic_curve::ops::Invert;
+        use k256::elliptic_curve::weierstrass::DecompressPoint;
+        use k256::{AffinePoint, FieldBytes, ProjectivePoint, Scalar};
+
+        let sign = {
+            use ecdsa::signature::Signature;
+            k256::ecdsa::recoverable::Signature::from_bytes(&sign.0).ok()?
+        };
+        let r = sign.r();
+        let s = sign.s();
+        let z = Scalar::from_bytes_reduced(FieldBytes::from_slice(msg_hash));
+
+        // the point whose `x` is `r`, with the parity of the recovery id
+        let y_is_odd = u8::from(sign.recovery_id()).into();
+        let big_r: AffinePoint = Option::from(AffinePoint::decompress(&r.to_bytes(), y_is_odd))?;
+
+        // pubkey = r⁻¹ (s R - z G)
+        let r_inv: Scalar = Option::from(r.invert())?;
+        let u1 = -(r_inv * z);
+        let u2 = r_inv * *s;
+        let point = ProjectivePoint::generator() * u1 + ProjectivePoint::from(big_r) * u2;
+
+        // unlike k256's own recovery, which panics on the identity
+        k256::PublicKey::from_affine(point.to_affine()).ok()
+    }
+}
@@ -112,22 +112,46 @@ impl From<k256::PublicKey> for PubKeyUncompressedNoHeader {
     }
 }
 
-impl From<near_sdk::PublicKey> for PubKeyUncompressedNoHeader {
-    fn from(pubkey: near_sdk::PublicKey) -> Self {
+impl std::convert::TryFrom<near_sdk::PublicKey> for PubKeyUncompressedNoHeader {
+    type Error = crate::error::Error;
+    /// Fails if the `pubkey` is not a secp256k1 key.
+    ///
+    /// The point is not checked.
+    fn try_from(pubkey: near_sdk::PublicKey) -> Result<Self, Self::Error> {
+        use crate::error::Error;
         use near_sdk::CurveType;
         match pubkey.curve_type() {
-            CurveType::ED25519 => panic!("wrong pubkey type"),
+            CurveType::ED25519 => Err(Error::UnknownCurve),
             CurveType::SECP256K1 => {
                 let pubkey = pubkey.as_bytes();
-                assert_eq!(pubkey.len(), 1 + 64);
+                if pubkey.len() != 1 + 64 {
+                    return Err(Error::WrongPubkeyLength);
+                }
                 let mut res = [0; 64];
                 res.copy_from_slice(&pubkey[1..]);
-                Self(res)
+                Ok(Self(res))
             }
         }
     }
 }
 
+impl std::convert::TryFrom<PubKeyCompressed> for PubKeyUncompressedNoHeader {
+    type Error = k256::elliptic_curve::Error;
+    /// Decompresses the pubkey, which must be a point on the curve.
+    fn try_from(pubkey: PubKeyCompressed) -> Result<Self, Self::Error> {
+        k256::PublicKey::from_sec1_bytes(&pubkey.0).map(Self::from)
+    }
+}
+
+impl std::convert::TryFrom<PubKeyUncompressed> for PubKeyUncompressedNoHeader {
+    type Error = k256::elliptic_curve::Error;
+    /// Removes the header of the pubkey, which must be a point on the
+    /// curve.
+    fn try_from(pubkey: PubKeyUncompressed) -> Result<Self, Self::Error> {
+        k256::PublicKey::from_sec1_bytes(&pubkey.0).map(Self::from)
+    }
+}
+
 impl From<PubKeyUncompressedNoHeader> for PubKeyUncompressed {
     fn from(pubkey: PubKeyUncompressedNoHeader) -> Self {
         // re-insert the header (0x04) as the first byte
@@ -164,6 +188,58 @@ impl From<k256::ecdsa::Signature> for SignCompact {
     }
 }
 
+/// Signature in DER encoded form, as some wallets emit it.
+///
+/// Has a variable size, of at most 72 bytes.
+///
+/// See also: [`crate::crypto::der`].
+#[derive(near_sdk::serde::Serialize, near_sdk::serde::Deserialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(transparent)]
+pub struct SignDer(pub Vec<u8>);
+
+impl SignDer {
+    /// Parses the strict DER encoding into the compact form.
+    ///
+    /// Returns `None` if the encoding is not strict DER, as defined
+    /// by BIP-66.
+    pub fn to_compact(&self) -> Option<SignCompact> {
+        crate::crypto::der::parse_ecdsa_signature(&self.0).map(SignCompact)
+    }
+}
+
+impl From<SignCompact> for SignDer {
+    fn from(sign: SignCompact) -> Self {
+        SignDer(crate::crypto::der::encode_ecdsa_signature(&sign.0))
+    }
+}
+
+/// How strictly a signature is checked.
+///
+/// Every signature `(r, s)` has a twin `(r, n - s)`, which is also
+/// valid for the same message and key. Both are accepted by default,
+/// as Near does, and only the lower-S one in the strict mode.
+#[derive(
+    near_sdk::serde::Serialize, near_sdk::serde::Deserialize, Clone, Copy, PartialEq, Debug,
+)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(rename_all = "snake_case")]
+pub enum VerifyMode {
+    /// Rejects signatures with a `s` in the higher half of the
+    /// curve's order, so that each signature has a single valid
+    /// encoding.
+    Strict,
+    /// Accepts signatures with a higher `s`, by normalizing them into
+    /// the lower-S form.
+    Lenient,
+}
+
+impl Default for VerifyMode {
+    fn default() -> Self {
+        VerifyMode::Lenient
+    }
+}
+
 /// Recoverable signature in serialized form.
 ///
 /// Has a total size of 65 bytes, containing:
@@ -192,11 +268,38 @@ impl From<k256::ecdsa::recoverable::Signature> for SignRecoverable {
     }
 }
 
-impl From<SignRecoverable> for SignCompact {
-    fn from(sign: SignRecoverable) -> Self {
+impl std::convert::TryFrom<SignRecoverable> for SignCompact {
+    type Error = crate::error::Error;
+    /// Fails if `r` or `s` are zero or out of range, or if the
+    /// recovery id is not `0` or `1`.
+    fn try_from(sign: SignRecoverable) -> Result<Self, Self::Error> {
         use ecdsa::signature::Signature;
-        let sign = k256::ecdsa::recoverable::Signature::from_bytes(&sign.0).unwrap();
+        let sign = k256::ecdsa::recoverable::Signature::from_bytes(&sign.0)
+            .map_err(|_| crate::error::Error::NonCanonicalSignature)?;
         let sign: k256::ecdsa::Signature = sign.into();
-        sign.into()
+        Ok(sign.into())
+    }
+}
+
+/// A public key recovered from a [`SignRecoverable`], in the
+/// different serialized forms.
+#[derive(near_sdk::serde::Serialize, near_sdk::serde::Deserialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RecoveredPubKey {
+    pub compressed: PubKeyCompressed,
+    pub uncompressed: PubKeyUncompressed,
+    /// The key as Near encodes it, ie. `secp256k1:` followed by the
+    /// base58 of [`PubKeyUncompressedNoHeader`].
+    pub near: crate::crypto::NearEncodedPubkey,
+}
+
+impl From<k256::PublicKey> for RecoveredPubKey {
+    fn from(pubkey: k256::PublicKey) -> Self {
+        let no_header: PubKeyUncompressedNoHeader = pubkey.into();
+        RecoveredPubKey {
+            compressed: pubkey.into(),
+            uncompressed: pubkey.into(),
+            near: no_header.into(),
+        }
     }
 }
@@ -1,4 +1,5 @@
 use super::types;
+use crate::error::{Error, OrPanicStr};
 use crate::{hash, Executor};
 use near_sdk::near_bindgen;
 
@@ -13,20 +14,24 @@ impl Executor {
     ///
     /// The `msg` is hashed using `sha256` and that is used
     /// to verify the signature's authenticity.
+    ///
+    /// The `mode` defaults to [`VerifyMode::Lenient`](types::VerifyMode::Lenient).
     pub fn ecdsa_secp256k1_verify_compressed_msg(
         pubkey: types::PubKeyCompressed,
         sign: types::SignCompact,
         msg: String,
+        mode: Option<types::VerifyMode>,
     ) -> bool {
-        Self::ecdsa_secp256k1_verify(&pubkey.0, sign, msg.as_bytes())
+        Self::ecdsa_secp256k1_verify(&pubkey.0, sign, msg.as_bytes(), mode.unwrap_or_default())
     }
 
     pub fn ecdsa_secp256k1_verify_uncompressed_msg(
         pubkey: types::PubKeyUncompressed,
         sign: types::SignCompact,
         msg: String,
+        mode: Option<types::VerifyMode>,
     ) -> bool {
-        Self::ecdsa_secp256k1_verify(&pubkey.0, sign, msg.as_bytes())
+        Self::ecdsa_secp256k1_verify(&pubkey.0, sign, msg.as_bytes(), mode.unwrap_or_default())
     }
 
     /// Returns `true` if `pubkey` authenticates the
@@ -35,12 +40,20 @@ impl Executor {
     ///
     /// The `msg_hash` must be the result of a `sha256` of the msg,
     /// and must have a total size of 32-bytes.
+    ///
+    /// The `mode` defaults to [`VerifyMode::Lenient`](types::VerifyMode::Lenient).
     pub fn ecdsa_secp256k1_verify_prehashed_compressed(
         pubkey: types::PubKeyCompressed,
         sign: types::SignCompact,
         hashed_msg: hash::Sha256,
+        mode: Option<types::VerifyMode>,
     ) -> bool {
-        Self::ecdsa_secp256k1_verify_prehashed(&pubkey.0, sign, hashed_msg)
+        Self::ecdsa_secp256k1_verify_prehashed(
+            &pubkey.0,
+            sign,
+            hashed_msg,
+            mode.unwrap_or_default(),
+        )
     }
 
     /// Returns `true` if `pubkey` authenticates the
@@ -49,12 +62,90 @@ impl Executor {
     ///
     /// The `msg_hash` must be the result of a `sha256` of the msg,
     /// and must have a total size of 32-bytes.
+    ///
+    /// The `mode` defaults to [`VerifyMode::Lenient`](types::VerifyMode::Lenient).
     pub fn ecdsa_secp256k1_verify_prehashed_uncompressed(
         pubkey: types::PubKeyUncompressed,
         sign: types::SignCompact,
         hashed_msg: hash::Sha256,
+        mode: Option<types::VerifyMode>,
+    ) -> bool {
+        Self::ecdsa_secp256k1_verify_prehashed(
+            &pubkey.0,
+            sign,
+            hashed_msg,
+            mode.unwrap_or_default(),
+        )
+    }
+
+    /// Same as
+    /// [`ecdsa_secp256k1_verify_compressed_msg()`](Self::ecdsa_secp256k1_verify_compressed_msg),
+    /// but the `sign` is DER encoded.
+    ///
+    /// Fails if the `sign` is not strict DER.
+    pub fn ecdsa_secp256k1_verify_der_compressed_msg(
+        pubkey: types::PubKeyCompressed,
+        sign: types::SignDer,
+        msg: String,
+        mode: Option<types::VerifyMode>,
+    ) -> bool {
+        let sign = parse_der(&sign);
+        Self::ecdsa_secp256k1_verify(&pubkey.0, sign, msg.as_bytes(), mode.unwrap_or_default())
+    }
+
+    /// Same as
+    /// [`ecdsa_secp256k1_verify_uncompressed_msg()`](Self::ecdsa_secp256k1_verify_uncompressed_msg),
+    /// but the `sign` is DER encoded.
+    ///
+    /// Fails if the `sign` is not strict DER.
+    pub fn ecdsa_secp256k1_verify_der_uncompressed_msg(
+        pubkey: types::PubKeyUncompressed,
+        sign: types::SignDer,
+        msg: String,
+        mode: Option<types::VerifyMode>,
+    ) -> bool {
+        let sign = parse_der(&sign);
+        Self::ecdsa_secp256k1_verify(&pubkey.0, sign, msg.as_bytes(), mode.unwrap_or_default())
+    }
+
+    /// Same as
+    /// [`ecdsa_secp256k1_verify_prehashed_compressed()`](Self::ecdsa_secp256k1_verify_prehashed_compressed),
+    /// but the `sign` is DER encoded.
+    ///
+    /// Fails if the `sign` is not strict DER.
+    pub fn ecdsa_secp256k1_verify_der_prehashed_compressed(
+        pubkey: types::PubKeyCompressed,
+        sign: types::SignDer,
+        hashed_msg: hash::Sha256,
+        mode: Option<types::VerifyMode>,
+    ) -> bool {
+        let sign = parse_der(&sign);
+        Self::ecdsa_secp256k1_verify_prehashed(
+            &pubkey.0,
+            sign,
+            hashed_msg,
+            mode.unwrap_or_default(),
+        )
+    }
+
+    /// Same as
+    /// [`ecdsa_secp256k1_verify_prehashed_uncompressed()`](Self::ecdsa_secp256k1_verify_prehashed_uncompressed),
+    /// but the `sign` is DER encoded.
+    ///
+    /// Fails if the `sign` is not strict DER.
+    pub fn ecdsa_secp256k1_verify_der_prehashed_uncompressed(
+        pubkey: types::PubKeyUncompressed,
+        sign: types::SignDer,
+        hashed_msg: hash::Sha256,
+        mode: Option<types::VerifyMode>,
     ) -> bool {
-        Self::ecdsa_secp256k1_verify_prehashed(&pubkey.0, sign, hashed_msg)
+        let sign = parse_der(&sign);
+        Self::ecdsa_secp256k1_verify_prehashed(
+            &pubkey.0,
+            sign,
+            hashed_msg,
+            mode.unwrap_or_default(),
+        )
     }
 }
 
@@ -69,28 +160,10 @@ impl Executor {
         pubkey: &[u8],
         sign: types::SignCompact,
         msg_bytes: &[u8],
+        mode: types::VerifyMode,
     ) -> bool {
-        // this is able to read both compressed and uncompressed pubkeys
-        let pubkey = k256::PublicKey::from_sec1_bytes(pubkey).unwrap();
-
-        let hashed_msg = {
-            use ecdsa::hazmat::FromDigest;
-            let hashed_msg = hash::Sha256::hash_bytes(msg_bytes);
-            k256::Scalar::from_digest(hashed_msg)
-        };
-
-        let sign = {
-            use k256::ecdsa::signature::Signature;
-            k256::ecdsa::Signature::from_bytes(&sign.0).unwrap()
-        };
-
-        {
-            use ecdsa::hazmat::VerifyPrimitive;
-            pubkey
-                .as_affine()
-                .verify_prehashed(&hashed_msg, &sign)
-                .is_ok()
-        }
+        let hashed_msg = hash::Sha256::hash_bytes(msg_bytes);
+        Self::ecdsa_secp256k1_verify_prehashed(pubkey, sign, hashed_msg, mode)
     }
 
     pub fn ecdsa_secp256k1_verify_compressed_msg_bytes(
@@ -98,7 +171,7 @@ impl Executor {
         sign: types::SignCompact,
         msg_bytes: &[u8],
     ) -> bool {
-        Self::ecdsa_secp256k1_verify(&pubkey.0, sign, msg_bytes)
+        Self::ecdsa_secp256k1_verify(&pubkey.0, sign, msg_bytes, types::VerifyMode::Strict)
     }
 
     pub fn ecdsa_secp256k1_verify_uncompressed_msg_bytes(
@@ -106,7 +179,7 @@ impl Executor {
         sign: types::SignCompact,
         msg_bytes: &[u8],
     ) -> bool {
-        Self::ecdsa_secp256k1_verify(&pubkey.0, sign, msg_bytes)
+        Self::ecdsa_secp256k1_verify(&pubkey.0, sign, msg_bytes, types::VerifyMode::Strict)
     }
 
     /// Returns `true` if `pubkey` authenticates the
@@ -115,29 +188,75 @@ impl Executor {
     ///
     /// The `msg_hash` must be the result of a `sha256` of the msg,
     /// and must have a total size of 32-bytes.
+    ///
+    /// With [`VerifyMode::Strict`](types::VerifyMode::Strict), a `sign`
+    /// in the higher-S form doesn't match.  
+    /// A `pubkey` that is not a point on the curve, or a `sign` with a
+    /// zero or out of range `r` or `s`, doesn't match either.
     pub fn ecdsa_secp256k1_verify_prehashed(
         pubkey: &[u8],
         sign: types::SignCompact,
         hashed_msg: hash::Sha256,
+        mode: types::VerifyMode,
     ) -> bool {
-        let pubkey = k256::PublicKey::from_sec1_bytes(pubkey).unwrap();
-
-        let hashed_msg = {
-            use ecdsa::hazmat::FromDigest;
-            k256::Scalar::from_digest(hashed_msg)
+        // this is able to read both compressed and uncompressed pubkeys
+        let pubkey = match k256::PublicKey::from_sec1_bytes(pubkey) {
+            Ok(pubkey) => pubkey,
+            Err(_) => return false,
         };
 
-        let sign = {
+        let mut sign = {
             use k256::ecdsa::signature::Signature;
-            k256::ecdsa::Signature::from_bytes(&sign.0).unwrap()
+            match k256::ecdsa::Signature::from_bytes(&sign.0) {
+                Ok(sign) => sign,
+                Err(_) => return false,
+            }
         };
 
-        {
-            use ecdsa::hazmat::VerifyPrimitive;
-            pubkey
-                .as_affine()
-                .verify_prehashed(&hashed_msg, &sign)
-                .is_ok()
+        match mode {
+            types::VerifyMode::Strict if bool::from(sign.s().is_high()) => return false,
+            types::VerifyMode::Strict => {}
+            // the verification itself only accepts the lower-S form
+            types::VerifyMode::Lenient => {
+                if sign.normalize_s().is_err() {
+                    return false;
+                }
+            }
         }
+
+        verify_prehashed(&pubkey, &sign, hashed_msg)
     }
 }
+
+fn parse_der(sign: &types::SignDer) -> types::SignCompact {
+    sign.to_compact().or_panic_str(Error::BadDerSignature)
+}
+
+#[cfg(not(all(feature = "host_crypto", target_arch = "wasm32")))]
+fn verify_prehashed(
+    pubkey: &k256::PublicKey,
+    sign: &k256::ecdsa::Signature,
+    hashed_msg: hash::Sha256,
+) -> bool {
+    let hashed_msg = {
+        use ecdsa::hazmat::FromDigest;
+        k256::Scalar::from_digest(hashed_msg)
+    };
+
+    use ecdsa::hazmat::VerifyPrimitive;
+    pubkey
+        .as_affine()
+        .verify_prehashed(&hashed_msg, sign)
+        .is_ok()
+}
+
+#[cfg(all(feature = "host_crypto", target_arch = "wasm32"))]
+fn verify_prehashed(
+    pubkey: &k256::PublicKey,
+    sign: &k256::ecdsa::Signature,
+    hashed_msg: hash::Sha256,
+) -> bool {
+    let mut sign_bytes = [0; 64];
+    sign_bytes.copy_from_slice(sign.as_ref());
+    crate::crypto::host::ecdsa_secp256k1_verify(pubkey, &sign_bytes, &hashed_msg.0)
+}
@@ -0,0 +1,117 @@
+use super::types;
+use crate::{hash, Executor};
+use near_sdk::near_bindgen;
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::ExecutorContract;
+
+#[near_bindgen]
+impl Executor {
+    /// Returns `true` if `pubkey` authenticates the
+    /// `sign` of the `msg_hash`.  
+    /// Returns `false` otherwise.
+    ///
+    /// The `msg` is hashed using `sha256` and that is used
+    /// to verify the signature's authenticity.
+    pub fn ecdsa_secp256r1_verify_compressed_msg(
+        pubkey: types::PubKeyCompressed,
+        sign: types::SignCompact,
+        msg: String,
+    ) -> bool {
+        Self::ecdsa_secp256r1_verify(&pubkey.0, sign, msg.as_bytes())
+    }
+
+    pub fn ecdsa_secp256r1_verify_uncompressed_msg(
+        pubkey: types::PubKeyUncompressed,
+        sign: types::SignCompact,
+        msg: String,
+    ) -> bool {
+        Self::ecdsa_secp256r1_verify(&pubkey.0, sign, msg.as_bytes())
+    }
+
+    /// Returns `true` if `pubkey` authenticates the
+    /// `sign` of the `sha256` hash of the `msg`.  
+    /// Returns `false` otherwise.
+    ///
+    /// The `msg_hash` must be the result of a `sha256` of the msg,
+    /// and must have a total size of 32-bytes.
+    pub fn ecdsa_secp256r1_verify_prehashed_compressed(
+        pubkey: types::PubKeyCompressed,
+        sign: types::SignCompact,
+        hashed_msg: hash::Sha256,
+    ) -> bool {
+        Self::ecdsa_secp256r1_verify_prehashed(&pubkey.0, sign, hashed_msg)
+    }
+
+    /// Returns `true` if `pubkey` authenticates the
+    /// `sign` of the `sha256` hash of the `msg`.  
+    /// Returns `false` otherwise.
+    ///
+    /// The `msg_hash` must be the result of a `sha256` of the msg,
+    /// and must have a total size of 32-bytes.
+    pub fn ecdsa_secp256r1_verify_prehashed_uncompressed(
+        pubkey: types::PubKeyUncompressed,
+        sign: types::SignCompact,
+        hashed_msg: hash::Sha256,
+    ) -> bool {
+        Self::ecdsa_secp256r1_verify_prehashed(&pubkey.0, sign, hashed_msg)
+    }
+}
+
+impl Executor {
+    /// Returns `true` if `pubkey` authenticates the
+    /// `sign` of the `msg_hash`.  
+    /// Returns `false` otherwise.
+    ///
+    /// The `msg` is hashed using `sha256` and that is used
+    /// to verify the signature's authenticity.
+    pub fn ecdsa_secp256r1_verify(
+        pubkey: &[u8],
+        sign: types::SignCompact,
+        msg_bytes: &[u8],
+    ) -> bool {
+        let hashed_msg = hash::Sha256::hash_bytes(msg_bytes);
+        Self::ecdsa_secp256r1_verify_prehashed(pubkey, sign, hashed_msg)
+    }
+
+    /// Returns `true` if `pubkey` authenticates the
+    /// `sign` of the `sha256` hash of the `msg`.  
+    /// Returns `false` otherwise.
+    ///
+    /// Differently from secp256k1, signatures in the higher-S form
+    /// are also accepted, as P-256 signers (such as WebAuthn
+    /// authenticators) don't normalize them.
+    ///
+    /// Returns `false` if the `pubkey` is not a point on the curve, or
+    /// if `r` or `s` of the `sign` are zero or out of range.
+    pub fn ecdsa_secp256r1_verify_prehashed(
+        pubkey: &[u8],
+        sign: types::SignCompact,
+        hashed_msg: hash::Sha256,
+    ) -> bool {
+        // this is able to read both compressed and uncompressed pubkeys
+        let pubkey = match p256::PublicKey::from_sec1_bytes(pubkey) {
+            Ok(pubkey) => pubkey,
+            Err(_) => return false,
+        };
+
+        let sign = {
+            use p256::ecdsa::signature::Signature;
+            match p256::ecdsa::Signature::from_bytes(&sign.0) {
+                Ok(sign) => sign,
+                Err(_) => return false,
+            }
+        };
+
+        let hashed_msg = {
+            use ecdsa::hazmat::FromDigest;
+            p256::Scalar::from_digest(hashed_msg)
+        };
+
+        use ecdsa::hazmat::VerifyPrimitive;
+        pubkey
+            .as_affine()
+            .verify_prehashed(&hashed_msg, &sign)
+            .is_ok()
+    }
+}
@@ -1,11 +1,10 @@
 use std::convert::TryFrom;
 
-// todo: replace this by an extended key, as this
-// is what nearcore uses
-//
-/// Private Key value.
+/// Private Key value, ie. the seed.
 ///
 /// Has a total size of 32 bytes.
+///
+/// See also: [`SecKeyExtended`], which is what nearcore uses.
 #[derive(near_sdk::serde::Serialize, near_sdk::serde::Deserialize, Clone, PartialEq, Debug)]
 #[serde(crate = "near_sdk::serde")]
 #[serde(transparent)]
@@ -15,6 +14,57 @@ pub struct SecKey(
     pub [u8; ed25519_dalek::SECRET_KEY_LENGTH],
 );
 
+/// Private Key value in the extended form that nearcore and near-cli
+/// use, eg. on the `ed25519:<base58>` secret keys of the credential
+/// files.
+///
+/// Has a total size of 64 bytes, containing:
+///
+/// - `seed` (32-bytes), the [`SecKey`];
+/// - `pubkey` (32-bytes), the [`PubKey`] derived from the `seed`.
+#[derive(near_sdk::serde::Serialize, near_sdk::serde::Deserialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(transparent)]
+pub struct SecKeyExtended(
+    #[serde(with = "serde_big_array::BigArray")]
+    //
+    pub [u8; ed25519_dalek::KEYPAIR_LENGTH],
+);
+
+impl SecKeyExtended {
+    pub fn seed(&self) -> SecKey {
+        let mut res = [0; ed25519_dalek::SECRET_KEY_LENGTH];
+        res.copy_from_slice(&self.0[..ed25519_dalek::SECRET_KEY_LENGTH]);
+        SecKey(res)
+    }
+
+    /// The public key as it's stored, which is not checked against
+    /// the `seed`.
+    ///
+    /// See also: [`Self::is_consistent()`].
+    pub fn pubkey(&self) -> PubKey {
+        let mut res = [0; ed25519_dalek::PUBLIC_KEY_LENGTH];
+        res.copy_from_slice(&self.0[ed25519_dalek::SECRET_KEY_LENGTH..]);
+        PubKey(res)
+    }
+
+    /// Whether the stored public key is the one derived from the
+    /// `seed`.
+    pub fn is_consistent(&self) -> bool {
+        PubKey::from(&self.seed()) == self.pubkey()
+    }
+}
+
+impl From<SecKey> for SecKeyExtended {
+    fn from(seckey: SecKey) -> Self {
+        let pubkey = PubKey::from(&seckey);
+        let mut res = [0; ed25519_dalek::KEYPAIR_LENGTH];
+        res[..ed25519_dalek::SECRET_KEY_LENGTH].copy_from_slice(&seckey.0);
+        res[ed25519_dalek::SECRET_KEY_LENGTH..].copy_from_slice(&pubkey.0);
+        SecKeyExtended(res)
+    }
+}
+
 /// Public Key value.  
 ///
 /// Has a total size of 32 bytes.
@@ -27,30 +77,99 @@ pub struct PubKey(
     pub [u8; ed25519_dalek::PUBLIC_KEY_LENGTH],
 );
 
+impl PubKey {
+    /// Checks that the key is a canonically encoded point which is
+    /// not of a small order, without verifying anything.
+    pub fn validity(&self) -> PubKeyValidity {
+        match decompress_strict(&self.0) {
+            Ok(_) => PubKeyValidity::Valid,
+            Err(validity) => validity,
+        }
+    }
+}
+
+/// Whether a [`PubKey`] can be used for the strict verification.
+///
+/// See [`VerifyMode::Strict`].
+#[derive(
+    near_sdk::serde::Serialize, near_sdk::serde::Deserialize, Clone, Copy, PartialEq, Debug,
+)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(rename_all = "snake_case")]
+pub enum PubKeyValidity {
+    Valid,
+    /// The `y` coordinate has no matching `x`.
+    NotAPoint,
+    /// The point is encoded with a `y` that is not reduced, or with
+    /// the sign of a zero `x`, so it has another encoding.
+    NonCanonical,
+    /// The point is of order 8 or less. Such a key matches the
+    /// signatures of many messages that were never signed.
+    SmallOrder,
+}
+
+/// Decompresses the point, which must be canonically encoded and not
+/// of a small order.
+pub(crate) fn decompress_strict(
+    bytes: &[u8; 32],
+) -> Result<curve25519_dalek::edwards::EdwardsPoint, PubKeyValidity> {
+    use curve25519_dalek::edwards::CompressedEdwardsY;
+    let point = CompressedEdwardsY(*bytes)
+        .decompress()
+        .ok_or(PubKeyValidity::NotAPoint)?;
+    if point.compress().0 != *bytes {
+        return Err(PubKeyValidity::NonCanonical);
+    }
+    if point.is_small_order() {
+        return Err(PubKeyValidity::SmallOrder);
+    }
+    Ok(point)
+}
+
 impl From<ed25519_dalek::PublicKey> for PubKey {
     fn from(pubkey: ed25519_dalek::PublicKey) -> Self {
         PubKey(pubkey.to_bytes())
     }
 }
 
-impl From<PubKey> for ed25519_dalek::PublicKey {
-    fn from(val: PubKey) -> Self {
-        ed25519_dalek::PublicKey::from_bytes(&val.0).unwrap()
+impl From<&SecKey> for PubKey {
+    fn from(seckey: &SecKey) -> Self {
+        let seckey = ed25519_dalek::SecretKey::from_bytes(&seckey.0).unwrap();
+        let pubkey: ed25519_dalek::PublicKey = (&seckey).into();
+        pubkey.into()
     }
 }
 
-impl From<near_sdk::PublicKey> for PubKey {
-    fn from(pubkey: near_sdk::PublicKey) -> Self {
+impl std::convert::TryFrom<PubKey> for ed25519_dalek::PublicKey {
+    type Error = crate::error::Error;
+    /// Fails if the `pubkey` is not the encoding of a point.
+    ///
+    /// The point is not checked to be canonical, nor of a large order.
+    fn try_from(pubkey: PubKey) -> Result<Self, Self::Error> {
+        ed25519_dalek::PublicKey::from_bytes(&pubkey.0)
+            .map_err(|_| crate::error::Error::InvalidPoint)
+    }
+}
+
+impl std::convert::TryFrom<near_sdk::PublicKey> for PubKey {
+    type Error = crate::error::Error;
+    /// Fails if the `pubkey` is not an ed25519 key.
+    ///
+    /// The point is not checked.
+    fn try_from(pubkey: near_sdk::PublicKey) -> Result<Self, Self::Error> {
+        use crate::error::Error;
         use near_sdk::CurveType;
         match pubkey.curve_type() {
             CurveType::ED25519 => {
                 let pubkey = pubkey.as_bytes();
-                assert_eq!(pubkey.len(), ed25519_dalek::PUBLIC_KEY_LENGTH + 1);
+                if pubkey.len() != ed25519_dalek::PUBLIC_KEY_LENGTH + 1 {
+                    return Err(Error::WrongPubkeyLength);
+                }
                 let mut res = [0; ed25519_dalek::PUBLIC_KEY_LENGTH];
                 res.copy_from_slice(&pubkey[1..]);
-                PubKey(res)
+                Ok(PubKey(res))
             }
-            CurveType::SECP256K1 => panic!("wrong pubkey type"),
+            CurveType::SECP256K1 => Err(Error::UnknownCurve),
         }
     }
 }
@@ -83,6 +202,36 @@ impl From<ed25519_dalek::Signature> for Sign {
     }
 }
 
+/// How strictly a [`Sign`] is checked.
+#[derive(
+    near_sdk::serde::Serialize, near_sdk::serde::Deserialize, Clone, Copy, PartialEq, Debug,
+)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(rename_all = "snake_case")]
+pub enum VerifyMode {
+    /// The same as Near's, which accepts public keys and `R` points of
+    /// a small order or that are not canonically encoded, but not `s`
+    /// values that are not fully reduced.
+    ///
+    /// Malformed public keys and signatures are not verified.
+    Lenient,
+    /// Also requires the public key to be [valid](PubKeyValidity),
+    /// the `R` point to be canonically encoded and not of a small
+    /// order, and the `s` to be reduced, so that each signature has a
+    /// single valid encoding.
+    ///
+    /// Keys and `R` points of a mixed order, ie. with a small torsion
+    /// component, are still accepted, as on ed25519-dalek's
+    /// `verify_strict()`.
+    Strict,
+}
+
+impl Default for VerifyMode {
+    fn default() -> Self {
+        VerifyMode::Lenient
+    }
+}
+
 /// Signature in serialized form, formed from a prehashed message.  
 /// Note that this Signature itself is not "prehashed".
 ///
@@ -1,4 +1,5 @@
 use super::types;
+use crate::error::Error;
 use crate::{hash, Executor};
 use near_sdk::near_bindgen;
 
@@ -7,16 +8,32 @@ use crate::ExecutorContract;
 
 #[near_bindgen]
 impl Executor {
+    /// Verifies the `sign` of the `msg_bytes`, as by the `mode`,
+    /// which is lenient by default.
     pub fn eddsa_ed25519_verify_bytes(
         pubkey: types::PubKey,
         sign: types::Sign,
         msg_bytes: Vec<u8>,
+        mode: Option<types::VerifyMode>,
     ) -> bool {
-        Self::eddsa_ed25519_verify(pubkey, sign, &msg_bytes)
+        Self::eddsa_ed25519_verify_with(pubkey, sign, &msg_bytes, mode.unwrap_or_default())
     }
 
-    pub fn eddsa_ed25519_verify_msg(pubkey: types::PubKey, sign: types::Sign, msg: String) -> bool {
-        Self::eddsa_ed25519_verify(pubkey, sign, &msg.as_bytes())
+    /// Verifies the `sign` of the `msg`, as by the `mode`, which is
+    /// lenient by default.
+    pub fn eddsa_ed25519_verify_msg(
+        pubkey: types::PubKey,
+        sign: types::Sign,
+        msg: String,
+        mode: Option<types::VerifyMode>,
+    ) -> bool {
+        Self::eddsa_ed25519_verify_with(pubkey, sign, msg.as_bytes(), mode.unwrap_or_default())
+    }
+
+    /// Checks that the `pubkey` is a canonically encoded point which
+    /// is not of a small order, as the strict verification requires.
+    pub fn eddsa_ed25519_validate_pubkey(pubkey: types::PubKey) -> types::PubKeyValidity {
+        pubkey.validity()
     }
 
     pub fn eddsa_ed25519_verify_prehashed(
@@ -25,8 +42,10 @@ impl Executor {
         msg_hash: hash::Sha512,
         context: Option<String>,
     ) -> bool {
-        let pubkey = ed25519_dalek::PublicKey::from_bytes(&pubkey.0).unwrap();
-        let sign = ed25519_dalek::Signature::from_bytes(&sign.0).unwrap();
+        let (pubkey, sign) = match dalek_inputs(&pubkey.0, &sign.0) {
+            Some(inputs) => inputs,
+            None => return false,
+        };
         let context = context.as_ref().map(|s| s.as_bytes());
         pubkey.verify_prehashed(msg_hash, context, &sign).is_ok()
     }
@@ -38,12 +57,163 @@ impl Executor {
         sign: types::Sign,
         msg_bytes: &[u8],
     ) -> bool {
-        let pubkey = ed25519_dalek::PublicKey::from_bytes(&pubkey.0).unwrap();
-        let sign = ed25519_dalek::Signature::from_bytes(&sign.0).unwrap();
+        match dalek_inputs(&pubkey.0, &sign.0) {
+            Some((pubkey, sign)) => verify(&pubkey, &sign, msg_bytes),
+            None => false,
+        }
+    }
+
+    pub fn eddsa_ed25519_verify_with(
+        pubkey: types::PubKey,
+        sign: types::Sign,
+        msg_bytes: &[u8],
+        mode: types::VerifyMode,
+    ) -> bool {
+        match mode {
+            types::VerifyMode::Lenient => Self::eddsa_ed25519_verify(pubkey, sign, msg_bytes),
+            types::VerifyMode::Strict => Self::eddsa_ed25519_verify_strict(pubkey, sign, msg_bytes),
+        }
+    }
+
+    /// Verifies the `sign` of the `msg_bytes` as by
+    /// [`types::VerifyMode::Strict`].
+    ///
+    /// This is always done inside of the contract, as the
+    /// `ed25519_verify` host function is not strict.
+    pub fn eddsa_ed25519_verify_strict(
+        pubkey: types::PubKey,
+        sign: types::Sign,
+        msg_bytes: &[u8],
+    ) -> bool {
+        use curve25519_dalek::edwards::EdwardsPoint;
+        use curve25519_dalek::scalar::Scalar;
+        use sha2::Digest;
 
-        {
-            use ed25519_dalek::Verifier;
-            pubkey.verify(msg_bytes, &sign).is_ok()
+        let a = match types::decompress_strict(&pubkey.0) {
+            Ok(a) => a,
+            Err(_) => return false,
+        };
+        let mut r = [0; 32];
+        r.copy_from_slice(&sign.0[..32]);
+        if types::decompress_strict(&r).is_err() {
+            return false;
         }
+        let mut s = [0; 32];
+        s.copy_from_slice(&sign.0[32..]);
+        let s = match Scalar::from_canonical_bytes(s) {
+            Some(s) => s,
+            None => return false,
+        };
+
+        let k = Scalar::from_hash(
+            sha2::Sha512::new()
+                .chain(&r)
+                .chain(&pubkey.0)
+                .chain(msg_bytes),
+        );
+        // R = sB - kA, without multiplying by the cofactor
+        let expected_r = EdwardsPoint::vartime_double_scalar_mul_basepoint(&k, &-a, &s);
+        expected_r.compress().0 == r
     }
+
+    /// Verifies a `sign` of the `msg_bytes` with the `Ed25519ctx`
+    /// algorithm, which binds the signature to a `context`.
+    ///
+    /// As with [`Self::eddsa_ed25519_verify_strict()`], `A` and `R`
+    /// must be canonically encoded points which are not of a small
+    /// order.
+    ///
+    /// The `context` must have between 1 and 255 bytes, otherwise the
+    /// signature is not verified.
+    pub fn eddsa_ed25519_verify_ctx(
+        pubkey: types::PubKey,
+        sign: types::Sign,
+        msg_bytes: &[u8],
+        context: &[u8],
+    ) -> bool {
+        use curve25519_dalek::edwards::EdwardsPoint;
+        use curve25519_dalek::scalar::Scalar;
+        use sha2::Digest;
+
+        if context.is_empty() {
+            return false;
+        }
+        let dom2 = match dom2(context) {
+            Ok(dom2) => dom2,
+            Err(_) => return false,
+        };
+        let a = match types::decompress_strict(&pubkey.0) {
+            Ok(a) => a,
+            Err(_) => return false,
+        };
+        let mut r = [0; 32];
+        r.copy_from_slice(&sign.0[..32]);
+        if types::decompress_strict(&r).is_err() {
+            return false;
+        }
+        let mut s = [0; 32];
+        s.copy_from_slice(&sign.0[32..]);
+        let s = match Scalar::from_canonical_bytes(s) {
+            Some(s) => s,
+            None => return false,
+        };
+
+        let k = Scalar::from_hash(
+            sha2::Sha512::new()
+                .chain(dom2)
+                .chain(&r)
+                .chain(&pubkey.0)
+                .chain(msg_bytes),
+        );
+        // R = sB - kA
+        let expected_r = EdwardsPoint::vartime_double_scalar_mul_basepoint(&k, &-a, &s);
+        expected_r.compress().0 == r
+    }
+}
+
+/// The `dom2(0, context)` prefix of
+/// [rfc8032](https://datatracker.ietf.org/doc/html/rfc8032#section-5.1),
+/// which is hashed before the rest of an `Ed25519ctx` signature, and
+/// which separates it from `Ed25519` and `Ed25519ph`.
+///
+/// Fails if the `context` has more than 255 bytes.
+pub fn dom2(context: &[u8]) -> Result<Vec<u8>, Error> {
+    if context.len() > 255 {
+        return Err(Error::BadContext);
+    }
+    let mut res = b"SigEd25519 no Ed25519 collisions".to_vec();
+    res.push(0);
+    res.push(context.len() as u8);
+    res.extend_from_slice(context);
+    Ok(res)
+}
+
+/// Parses the `pubkey` and the `sign`, which fails if the `pubkey`
+/// is not a point or if the `s` of the `sign` is not canonical.
+fn dalek_inputs(
+    pubkey: &[u8; 32],
+    sign: &[u8; 64],
+) -> Option<(ed25519_dalek::PublicKey, ed25519_dalek::Signature)> {
+    let pubkey = ed25519_dalek::PublicKey::from_bytes(pubkey).ok()?;
+    let sign = ed25519_dalek::Signature::from_bytes(sign).ok()?;
+    Some((pubkey, sign))
+}
+
+#[cfg(not(all(feature = "host_crypto", target_arch = "wasm32")))]
+fn verify(
+    pubkey: &ed25519_dalek::PublicKey,
+    sign: &ed25519_dalek::Signature,
+    msg_bytes: &[u8],
+) -> bool {
+    use ed25519_dalek::Verifier;
+    pubkey.verify(msg_bytes, sign).is_ok()
+}
+
+#[cfg(all(feature = "host_crypto", target_arch = "wasm32"))]
+fn verify(
+    pubkey: &ed25519_dalek::PublicKey,
+    sign: &ed25519_dalek::Signature,
+    msg_bytes: &[u8],
+) -> bool {
+    crate::crypto::host::ed25519_verify(pubkey.as_bytes(), &sign.to_bytes(), msg_bytes)
 }
@@ -0,0 +1,78 @@
+//! The text encodings of signatures and public keys.
+
+use crate::error::Error;
+
+/// An encoding of bytes into text.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Encoding {
+    Base58,
+    /// With the standard alphabet and padding.
+    Base64,
+    Hex,
+}
+
+impl Encoding {
+    /// The `{tag}:` prefix that selects the encoding.
+    pub fn tag(&self) -> &'static str {
+        match self {
+            Encoding::Base58 => "base58",
+            Encoding::Base64 => "base64",
+            Encoding::Hex => "hex",
+        }
+    }
+
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        [Encoding::Base58, Encoding::Base64, Encoding::Hex]
+            .iter()
+            .copied()
+            .find(|encoding| encoding.tag() == tag)
+    }
+
+    /// Detects the encoding of `s`, returning it together with the
+    /// encoded data.
+    ///
+    /// The encoding is either tagged, eg. `base64:<data>`, or is
+    /// `0x`-prefixed hex. Otherwise it's base58.
+    pub fn detect(s: &str) -> (Self, &str) {
+        if let Some((tag, data)) = s.split_once(':') {
+            if let Some(encoding) = Self::from_tag(tag) {
+                return (encoding, data);
+            }
+        }
+        match s.strip_prefix("0x") {
+            Some(data) => (Encoding::Hex, data),
+            None => (Encoding::Base58, s),
+        }
+    }
+
+    pub fn decode(&self, data: &str) -> Result<Vec<u8>, Error> {
+        match self {
+            Encoding::Base58 => near_sdk::bs58::decode(data)
+                .into_vec()
+                .map_err(|_| Error::BadBase58),
+            Encoding::Base64 => near_sdk::base64::decode(data).map_err(|_| Error::BadBase64),
+            Encoding::Hex => decode_hex(data).ok_or(Error::BadHex),
+        }
+    }
+}
+
+/// Decodes `s` with the encoding that it has.
+///
+/// See also: [`Encoding::detect()`].
+pub fn decode(s: &str) -> Result<Vec<u8>, Error> {
+    let (encoding, data) = Encoding::detect(s);
+    encoding.decode(data)
+}
+
+/// Decodes a hex string, with an optional `0x` prefix.
+pub fn decode_hex(s: &str) -> Option<Vec<u8>> {
+    let s = s.strip_prefix("0x").unwrap_or(s);
+    // from_str_radix also accepts a leading `+`
+    if s.len() % 2 != 0 || !s.bytes().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok())
+        .collect()
+}
@@ -0,0 +1,127 @@
+//! Messages signed inside of an envelope, which binds them to an app's
+//! domain, to a contract and to a chain, and which expires.
+//!
+//! Unlike with [`Executor::verify_msg()`], a signed envelope can't be
+//! replayed against another app, contract or chain.
+
+use super::ecdsa_secp256k1::types::VerifyMode;
+use super::nep413::Nonce;
+use super::{Bs58EncodedSignature, NearEncodedPubkey};
+use crate::{hash, Executor};
+use near_sdk::borsh::{self, BorshSerialize};
+use near_sdk::json_types::U64;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{env, near_bindgen, AccountId};
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::ExecutorContract;
+
+/// The tag of the envelope's hash, which separates it from the hashes
+/// of other messages.
+///
+/// See [`hash::Sha256::hash_tagged()`].
+pub const ENVELOPE_TAG: &str = "nearapps/envelope";
+
+/// The longest that an envelope can be valid for, from its
+/// `issued_at` to its `expires_at`, of one day in nanoseconds.
+pub const MAX_VALIDITY: u64 = 24 * 60 * 60 * 1_000_000_000;
+
+/// How far ahead of the block timestamp the `issued_at` can be, as
+/// the signer's clock can be ahead of it, of five minutes in
+/// nanoseconds.
+pub const MAX_CLOCK_SKEW: u64 = 5 * 60 * 1_000_000_000;
+
+/// The Near network.
+#[derive(Serialize, Deserialize, BorshSerialize, Clone, Copy, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(rename_all = "snake_case")]
+pub enum ChainId {
+    Mainnet,
+    Testnet,
+}
+
+impl ChainId {
+    /// The chain of the `account_id`, by its top-level account, ie.
+    /// `near` or `testnet`.
+    ///
+    /// Returns `None` for other accounts, eg. implicit accounts.
+    pub fn of_account(account_id: &AccountId) -> Option<Self> {
+        match account_id.as_str().rsplit('.').next() {
+            Some("near") => Some(ChainId::Mainnet),
+            Some("testnet") => Some(ChainId::Testnet),
+            _ => None,
+        }
+    }
+}
+
+/// What gets signed.
+#[derive(Serialize, Deserialize, BorshSerialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Envelope {
+    /// The app that the message is for, eg. its origin.
+    pub domain: String,
+    /// The contract that verifies the message.
+    pub contract_id: AccountId,
+    pub chain_id: ChainId,
+    /// Makes each envelope unique. Keeping track of the nonces that
+    /// were already used is up to the app.
+    pub nonce: Nonce,
+    /// When the envelope was signed, as a timestamp in nanoseconds.
+    pub issued_at: U64,
+    /// From when the envelope is no longer valid, as a timestamp in
+    /// nanoseconds. At most [`MAX_VALIDITY`] after the `issued_at`.
+    pub expires_at: U64,
+    pub payload: String,
+}
+
+impl Envelope {
+    /// The `sha256` of the borsh serialized envelope, tagged with
+    /// [`ENVELOPE_TAG`] as by [`hash::Sha256::hash_tagged()`].
+    ///
+    /// This is what gets signed, as
+    /// [`verify_hashed_msg()`](Executor::verify_hashed_msg) expects.
+    pub fn hash(&self) -> hash::Sha256 {
+        hash::Sha256::hash_tagged(ENVELOPE_TAG, &self.try_to_vec().unwrap())
+    }
+}
+
+#[near_bindgen]
+impl Executor {
+    /// Verifies that `pubkey` signed the `envelope`, and that the
+    /// envelope is for the `domain`, for this contract and for the
+    /// chain of this contract, and that it's currently valid.
+    ///
+    /// The chain is the one of the top-level account of this
+    /// contract, so an executor on another account, eg. an implicit
+    /// one, can't verify envelopes.  
+    /// The envelope must be valid for at most [`MAX_VALIDITY`], and
+    /// its `issued_at` can be at most [`MAX_CLOCK_SKEW`] ahead of the
+    /// block timestamp.
+    ///
+    /// `Secp256k1` signatures are verified in the
+    /// [`VerifyMode::Strict`] mode.
+    pub fn verify_envelope(
+        envelope: Envelope,
+        sign: Bs58EncodedSignature,
+        pubkey: NearEncodedPubkey,
+        domain: String,
+    ) -> bool {
+        let current_id = env::current_account_id();
+        if envelope.domain != domain
+            || Some(envelope.chain_id) != ChainId::of_account(&current_id)
+            || envelope.contract_id != current_id
+        {
+            return false;
+        }
+        let (issued_at, expires_at) = (envelope.issued_at.0, envelope.expires_at.0);
+        let now = env::block_timestamp();
+        if now >= expires_at
+            || issued_at > now.saturating_add(MAX_CLOCK_SKEW)
+            || expires_at.saturating_sub(issued_at) > MAX_VALIDITY
+        {
+            return false;
+        }
+        let msg_hash = envelope.hash();
+        Self::verify_inner(sign, pubkey.parse(), msg_hash, VerifyMode::Strict)
+    }
+}
@@ -0,0 +1,4 @@
+pub mod address;
+pub mod eip712;
+pub mod personal_sign;
+pub mod types;
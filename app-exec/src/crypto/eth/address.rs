@@ -0,0 +1,50 @@
+use super::types;
+use crate::crypto::{Bs58EncodedSignature, EcdsaSecp256k1PublicKey, NearEncodedPubkey};
+use crate::error::{Error, OrPanicStr};
+use crate::Executor;
+use near_sdk::near_bindgen;
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::ExecutorContract;
+
+#[near_bindgen]
+impl Executor {
+    /// The Ethereum address of the secp256k1 `pubkey`, with the EIP-55
+    /// checksum.
+    ///
+    /// The `pubkey` can be in any of the forms that
+    /// [`verify_msg()`](Self::verify_msg) accepts.
+    pub fn eth_address(pubkey: NearEncodedPubkey) -> types::ChecksumAddress {
+        use std::convert::TryFrom;
+        let pubkey =
+            EcdsaSecp256k1PublicKey::try_from(pubkey.parse()).unwrap_or_else(|e| e.panic());
+        types::Address::from(pubkey).into()
+    }
+
+    /// Verifies if the key of the Ethereum `address` signed `msg` with
+    /// `personal_sign`, as
+    /// [`verify_eth_personal_sign()`](Self::verify_eth_personal_sign)
+    /// would, but with the `sign` encoded as for
+    /// [`verify_msg()`](Self::verify_msg).
+    ///
+    /// The `sign` must have the recovery byte, which can also be
+    /// offset by `27` as Ethereum has it.
+    /// Returns `false` if the key can't be recovered, or if the
+    /// signature is in the higher-S form.
+    pub fn verify_by_eth_address(
+        address: types::ChecksumAddress,
+        sign: Bs58EncodedSignature,
+        msg: String,
+    ) -> bool {
+        let address = address.parse().or_panic_str(Error::BadEthAddress);
+        let sign = sign.try_decode().unwrap_or_else(|e| e.panic());
+        if sign.len() != 65 {
+            Error::WrongSignatureLength.panic();
+        }
+        let mut sign_raw = [0; 65];
+        sign_raw.copy_from_slice(&sign);
+
+        let msg_hash = Self::eth_personal_sign_hash(msg);
+        Self::eth_recover_address(&types::SignRsv(sign_raw), &msg_hash) == Some(address)
+    }
+}
@@ -0,0 +1,344 @@
+//! Typed structured data hashing and signing, as defined by
+//! [EIP-712](https://eips.ethereum.org/EIPS/eip-712).
+
+use super::types;
+use crate::crypto::encoding::decode_hex;
+use crate::error::{Error, OrPanicStr};
+use crate::hash::Keccak256;
+use crate::Executor;
+use near_sdk::near_bindgen;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::ExecutorContract;
+
+/// The name of the type that defines the domain separator.
+pub const DOMAIN_TYPE: &str = "EIP712Domain";
+
+/// A member of a struct type.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TypedField {
+    pub name: String,
+    /// eg. `"address"`, `"uint256"`, `"string"`, `"Person"` or
+    /// `"Person[]"`.
+    #[serde(rename = "type")]
+    pub type_: String,
+}
+
+/// The struct types, by their names.
+pub type Types = BTreeMap<String, Vec<TypedField>>;
+
+/// A message to be hashed and signed.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TypedData {
+    /// The hash of the domain struct.
+    ///
+    /// See [`Executor::eip712_hash_struct()`] with [`DOMAIN_TYPE`].
+    #[serde(alias = "domainSeparator")]
+    pub domain_separator: Keccak256,
+    pub types: Types,
+    /// The name of the type of `message`.
+    #[serde(alias = "primaryType")]
+    pub primary_type: String,
+    pub message: Value,
+}
+
+#[near_bindgen]
+impl Executor {
+    /// Creates the `hashStruct` of `data`, which is of the
+    /// `primary_type` type.
+    ///
+    /// Using [`DOMAIN_TYPE`] as the `primary_type` creates the domain
+    /// separator.
+    pub fn eip712_hash_struct(types: Types, primary_type: String, data: Value) -> Keccak256 {
+        hash_struct(&types, &primary_type, &data)
+    }
+
+    /// Creates the `keccak256` hash that Ethereum wallets sign on
+    /// `signTypedData`.
+    pub fn eip712_signing_hash(typed_data: TypedData) -> Keccak256 {
+        signing_hash(&typed_data)
+    }
+
+    /// Recovers the Ethereum address that signed `typed_data`.
+    ///
+    /// Note that any valid signature recovers to some address, so
+    /// the result must still be compared against the expected one.  
+    /// Fails if the signature is in the higher-S form.
+    pub fn eip712_recover(typed_data: TypedData, sign: types::SignRsv) -> types::Address {
+        let msg_hash = signing_hash(&typed_data);
+        Self::eth_recover_address(&sign, &msg_hash).or_panic_str(Error::RecoverFailed)
+    }
+
+    /// Returns `true` if `address` signed `typed_data`.
+    /// Returns `false` otherwise, including when `sign` is malformed or
+    /// in the higher-S form.
+    pub fn verify_eip712(
+        address: types::Address,
+        sign: types::SignRsv,
+        typed_data: TypedData,
+    ) -> bool {
+        let msg_hash = signing_hash(&typed_data);
+        Self::eth_recover_address(&sign, &msg_hash) == Some(address)
+    }
+}
+
+/// `keccak256(0x19 ‖ 0x01 ‖ domainSeparator ‖ hashStruct(message))`.
+pub fn signing_hash(typed_data: &TypedData) -> Keccak256 {
+    let struct_hash = hash_struct(
+        &typed_data.types,
+        &typed_data.primary_type,
+        &typed_data.message,
+    );
+    let mut bytes = vec![0x19, 0x01];
+    bytes.extend(&typed_data.domain_separator.0);
+    bytes.extend(&struct_hash.0);
+    Keccak256::hash_bytes(&bytes)
+}
+
+/// `keccak256(typeHash ‖ encodeData(data))`.
+pub fn hash_struct(types: &Types, name: &str, data: &Value) -> Keccak256 {
+    let fields = types.get(name).or_panic_str(Error::Eip712MissingType);
+
+    let mut bytes = type_hash(types, name).0.to_vec();
+    for field in fields {
+        let value = data
+            .get(&field.name)
+            .or_panic_str(Error::Eip712MissingField);
+        bytes.extend(&encode_value(types, &field.type_, value));
+    }
+    Keccak256::hash_bytes(&bytes)
+}
+
+/// `keccak256(encodeType(name))`.
+pub fn type_hash(types: &Types, name: &str) -> Keccak256 {
+    Keccak256::hash_bytes(encode_type(types, name).as_bytes())
+}
+
+/// The `name` type followed by the types that it references,
+/// sorted by name.
+///
+/// eg. `Mail(Person from,Person to,string contents)Person(string name,address wallet)`.
+pub fn encode_type(types: &Types, name: &str) -> String {
+    let mut dependencies = BTreeSet::new();
+    find_dependencies(types, name, &mut dependencies);
+    dependencies.remove(&name);
+
+    std::iter::once(name)
+        .chain(dependencies.into_iter())
+        .map(|name| {
+            let fields = types[name]
+                .iter()
+                .map(|field| format!("{} {}", field.type_, field.name))
+                .collect::<Vec<_>>()
+                .join(",");
+            format!("{}({})", name, fields)
+        })
+        .collect()
+}
+
+fn find_dependencies<'a>(types: &'a Types, name: &str, found: &mut BTreeSet<&'a str>) {
+    let (name, fields) = match types.get_key_value(base_type(name)) {
+        Some(entry) => entry,
+        None => return,
+    };
+    if !found.insert(name.as_str()) {
+        return;
+    }
+    for field in fields {
+        find_dependencies(types, &field.type_, found);
+    }
+}
+
+/// Removes the array suffixes, eg. `Person[][2]` into `Person`.
+fn base_type(type_: &str) -> &str {
+    type_.split('[').next().unwrap_or(type_)
+}
+
+/// Encodes a value into 32 bytes, according to its type.
+fn encode_value(types: &Types, type_: &str, value: &Value) -> [u8; 32] {
+    if let Some(item_type) = type_.strip_suffix(']') {
+        let open = item_type.rfind('[').unwrap_or_else(bad_value);
+        let (item_type, size) = (&item_type[..open], &item_type[open + 1..]);
+        let items = value.as_array().unwrap_or_else(bad_value);
+        // a fixed-size array, eg. `uint256[2]`, must have that many items
+        if !size.is_empty() {
+            let size = parse_size(size).unwrap_or_else(|| Error::Eip712UnknownType.panic());
+            if items.len() != size as usize {
+                bad_value()
+            }
+        }
+        let bytes: Vec<u8> = items
+            .iter()
+            .flat_map(|item| encode_value(types, item_type, item).to_vec())
+            .collect();
+        return Keccak256::hash_bytes(&bytes).0;
+    }
+
+    if types.contains_key(type_) {
+        return hash_struct(types, type_, value).0;
+    }
+
+    let mut res = [0u8; 32];
+    match type_ {
+        "string" => {
+            let s = value.as_str().unwrap_or_else(bad_value);
+            res = Keccak256::hash_bytes(s.as_bytes()).0;
+        }
+        "bytes" => {
+            let bytes = value
+                .as_str()
+                .and_then(decode_hex)
+                .unwrap_or_else(bad_value);
+            res = Keccak256::hash_bytes(&bytes).0;
+        }
+        "bool" => {
+            res[31] = value.as_bool().unwrap_or_else(bad_value) as u8;
+        }
+        "address" => {
+            let bytes = value
+                .as_str()
+                .and_then(decode_hex)
+                .unwrap_or_else(bad_value);
+            if bytes.len() != 20 {
+                bad_value()
+            }
+            res[12..].copy_from_slice(&bytes);
+        }
+        t if t.starts_with("bytes") => {
+            let size = match parse_size(&t["bytes".len()..]) {
+                Some(size @ 1..=32) => size as usize,
+                _ => Error::Eip712UnknownType.panic(),
+            };
+            let bytes = value
+                .as_str()
+                .and_then(decode_hex)
+                .unwrap_or_else(bad_value);
+            if bytes.len() > size {
+                bad_value()
+            }
+            // fixed-size bytes are right-padded
+            res[..bytes.len()].copy_from_slice(&bytes);
+        }
+        t if t.starts_with("uint") || t.starts_with("int") => {
+            res = encode_int(t, value);
+        }
+        _ => Error::Eip712UnknownType.panic(),
+    }
+    res
+}
+
+fn bad_value<T>() -> T {
+    Error::Eip712BadValue.panic()
+}
+
+/// Encodes an `uintN` or `intN` value, which must be in the range of
+/// its type.
+///
+/// Negative values are in two's complement.
+fn encode_int(type_: &str, value: &Value) -> [u8; 32] {
+    let (is_signed, bits) = match type_.strip_prefix("uint") {
+        Some(bits) => (false, bits),
+        None => (true, &type_["int".len()..]),
+    };
+    let bits = match parse_size(bits) {
+        Some(bits) if bits % 8 == 0 && (8..=256).contains(&bits) => bits,
+        _ => Error::Eip712UnknownType.panic(),
+    };
+
+    let (is_negative, mut res) = parse_int(value).unwrap_or_else(bad_value);
+    let len = bit_len(&res);
+    let is_in_range = match (is_signed, is_negative) {
+        (false, false) => len <= bits,
+        // only accepts `-0`
+        (false, true) => len == 0,
+        (true, false) => len < bits,
+        // down to `-2^(bits-1)`
+        (true, true) => {
+            len < bits || (len == bits && res.iter().map(|b| b.count_ones()).sum::<u32>() == 1)
+        }
+    };
+    if !is_in_range {
+        bad_value()
+    }
+
+    if is_negative {
+        // two's complement: inverts and adds one
+        let mut carry = 1;
+        for byte in res.iter_mut().rev() {
+            let v = (!*byte as u16) + carry;
+            *byte = v as u8;
+            carry = v >> 8;
+        }
+    }
+    res
+}
+
+/// Parses the size suffix of a type, eg. the `32` of `bytes32`.
+fn parse_size(s: &str) -> Option<u32> {
+    // parse also accepts a leading `+`
+    if s.is_empty() || !s.bytes().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    s.parse().ok()
+}
+
+/// How many bits the big-endian `n` takes, without its leading zeros.
+fn bit_len(n: &[u8; 32]) -> u32 {
+    match n.iter().position(|b| *b != 0) {
+        Some(i) => (32 - i as u32) * 8 - n[i].leading_zeros(),
+        None => 0,
+    }
+}
+
+/// Parses a json number, or a decimal or `0x`-prefixed hex string,
+/// into whether it's negative and its magnitude, in 32 big-endian
+/// bytes.
+fn parse_int(value: &Value) -> Option<(bool, [u8; 32])> {
+    let s = match value {
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => s.clone(),
+        _ => return None,
+    };
+    let (is_negative, s) = match s.strip_prefix('-') {
+        Some(s) => (true, s),
+        None => (false, s.as_str()),
+    };
+
+    if s.is_empty() {
+        return None;
+    }
+
+    let mut res = [0u8; 32];
+    if let Some(hex) = s.strip_prefix("0x") {
+        // eg. `0x1`, which has an odd number of nibbles
+        let bytes = if hex.len() % 2 == 1 {
+            decode_hex(&format!("0{}", hex))?
+        } else {
+            decode_hex(hex)?
+        };
+        if bytes.len() > 32 {
+            return None;
+        }
+        res[32 - bytes.len()..].copy_from_slice(&bytes);
+    } else {
+        for c in s.chars() {
+            // res = res * 10 + digit
+            let mut carry = c.to_digit(10)?;
+            for byte in res.iter_mut().rev() {
+                let v = (*byte as u32) * 10 + carry;
+                *byte = v as u8;
+                carry = v >> 8;
+            }
+            if carry != 0 {
+                return None;
+            }
+        }
+    }
+
+    Some((is_negative, res))
+}
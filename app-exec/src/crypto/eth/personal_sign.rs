@@ -0,0 +1,76 @@
+use super::types;
+use crate::error::{Error, OrPanicStr};
+use crate::hash::Keccak256;
+use crate::Executor;
+use near_sdk::near_bindgen;
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::ExecutorContract;
+
+/// The prefix of messages signed with `personal_sign`, as defined by
+/// [EIP-191](https://eips.ethereum.org/EIPS/eip-191).
+pub const PERSONAL_SIGN_PREFIX: &str = "\x19Ethereum Signed Message:\n";
+
+#[near_bindgen]
+impl Executor {
+    /// Creates the `keccak256` hash that Ethereum wallets sign on
+    /// `personal_sign`.
+    ///
+    /// That is the hash of the [`PERSONAL_SIGN_PREFIX`], followed by
+    /// the decimal length of `msg` in bytes and then by `msg`.
+    pub fn eth_personal_sign_hash(msg: String) -> Keccak256 {
+        let mut bytes = PERSONAL_SIGN_PREFIX.as_bytes().to_vec();
+        bytes.extend(msg.len().to_string().as_bytes());
+        bytes.extend(msg.as_bytes());
+        Keccak256::hash_bytes(&bytes)
+    }
+
+    /// Recovers the Ethereum address that signed `msg` with
+    /// `personal_sign`.
+    ///
+    /// Note that any valid signature recovers to some address, so
+    /// the result must still be compared against the expected one.  
+    /// Fails if the signature is in the higher-S form.
+    pub fn eth_personal_sign_recover(sign: types::SignRsv, msg: String) -> types::Address {
+        let msg_hash = Self::eth_personal_sign_hash(msg);
+        Self::eth_recover_address(&sign, &msg_hash).or_panic_str(Error::RecoverFailed)
+    }
+
+    /// Returns `true` if `address` signed `msg` with `personal_sign`.  
+    /// Returns `false` otherwise, including when `sign` is malformed or
+    /// in the higher-S form.
+    pub fn verify_eth_personal_sign(
+        address: types::Address,
+        sign: types::SignRsv,
+        msg: String,
+    ) -> bool {
+        let msg_hash = Self::eth_personal_sign_hash(msg);
+        Self::eth_recover_address(&sign, &msg_hash) == Some(address)
+    }
+}
+
+impl Executor {
+    /// Recovers the Ethereum address that created `sign` of the
+    /// `msg_hash`, which must be in the lower-S form, as
+    /// [EIP-2](https://eips.ethereum.org/EIPS/eip-2) requires.
+    ///
+    /// Returns `None` if the signature is malformed or in the higher-S
+    /// form.
+    pub fn eth_recover_address(
+        sign: &types::SignRsv,
+        msg_hash: &Keccak256,
+    ) -> Option<types::Address> {
+        let sign = sign.to_recoverable()?;
+        let is_high_s = {
+            use k256::ecdsa::signature::Signature;
+            let sign = k256::ecdsa::recoverable::Signature::from_bytes(&sign.0).ok()?;
+            let sign: k256::ecdsa::Signature = sign.into();
+            bool::from(sign.s().is_high())
+        };
+        if is_high_s {
+            return None;
+        }
+        let pubkey = Self::ecdsa_secp256k1_recover_pubkey(&sign, &msg_hash.0)?;
+        Some(pubkey.into())
+    }
+}
@@ -0,0 +1,150 @@
+use crate::crypto::ecdsa_secp256k1::types::{
+    PubKeyUncompressed, PubKeyUncompressedNoHeader, SignRecoverable,
+};
+use crate::crypto::encoding::decode_hex;
+use crate::hash::Keccak256;
+use near_sdk::serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+/// Ethereum address.
+///
+/// Has a total size of 20 bytes, which are the last 20 bytes of the
+/// `keccak256` hash of the [`PubKeyUncompressedNoHeader`].
+///
+/// Is serialized as a [`ChecksumAddress`], and deserialized from any
+/// address that it would parse.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Address(pub [u8; 20]);
+
+impl Serialize for Address {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ChecksumAddress::from(self.clone()).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Address {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        ChecksumAddress::deserialize(deserializer)?
+            .parse()
+            .ok_or_else(|| de::Error::custom("invalid Ethereum address"))
+    }
+}
+
+impl From<PubKeyUncompressedNoHeader> for Address {
+    fn from(pubkey: PubKeyUncompressedNoHeader) -> Self {
+        let hash = Keccak256::hash_bytes(&pubkey.0);
+        let mut res = [0; 20];
+        // skips the first 12 bytes
+        res.copy_from_slice(&hash.0[12..]);
+        Address(res)
+    }
+}
+
+impl From<PubKeyUncompressed> for Address {
+    /// The header is skipped, and is not checked.
+    fn from(pubkey: PubKeyUncompressed) -> Self {
+        let mut res = [0; 64];
+        res.copy_from_slice(&pubkey.0[1..]);
+        PubKeyUncompressedNoHeader(res).into()
+    }
+}
+
+impl From<k256::PublicKey> for Address {
+    fn from(pubkey: k256::PublicKey) -> Self {
+        let pubkey: PubKeyUncompressedNoHeader = pubkey.into();
+        pubkey.into()
+    }
+}
+
+/// Ethereum address in hex, with the mixed-case checksum of
+/// [EIP-55](https://eips.ethereum.org/EIPS/eip-55), eg.
+/// `0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed`.
+#[derive(near_sdk::serde::Serialize, near_sdk::serde::Deserialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(transparent)]
+pub struct ChecksumAddress(pub String);
+
+impl ChecksumAddress {
+    /// Parses the address, which must have the `0x` prefix.
+    ///
+    /// All-lowercase and all-uppercase addresses have no checksum, so
+    /// they are also accepted.  
+    /// Returns `None` if the address is malformed or if its checksum
+    /// doesn't match.
+    pub fn parse(&self) -> Option<Address> {
+        let hex = self.0.strip_prefix("0x")?;
+        // with the prefix, so it's not stripped twice
+        let bytes = decode_hex(&self.0)?;
+        if bytes.len() != 20 {
+            return None;
+        }
+        let mut res = [0; 20];
+        res.copy_from_slice(&bytes);
+        let address = Address(res);
+
+        let is_mixed_case = hex.bytes().any(|c| c.is_ascii_lowercase())
+            && hex.bytes().any(|c| c.is_ascii_uppercase());
+        if is_mixed_case && ChecksumAddress::from(address.clone()) != *self {
+            return None;
+        }
+        Some(address)
+    }
+}
+
+impl From<Address> for ChecksumAddress {
+    /// Each letter of the lowercase hex is uppercased if the matching
+    /// nibble of the `keccak256` of that hex is `8` or higher.
+    fn from(address: Address) -> Self {
+        let hex: String = address.0.iter().map(|b| format!("{:02x}", b)).collect();
+        let hash = Keccak256::hash_bytes(hex.as_bytes()).0;
+        let checksummed: String = hex
+            .chars()
+            .enumerate()
+            .map(|(i, c)| {
+                let nibble = (hash[i / 2] >> (4 * (1 - i % 2))) & 0xf;
+                if nibble >= 8 {
+                    c.to_ascii_uppercase()
+                } else {
+                    c
+                }
+            })
+            .collect();
+        ChecksumAddress(format!("0x{}", checksummed))
+    }
+}
+
+/// Recoverable signature in the serialized form used by Ethereum.
+///
+/// Has a total size of 65 bytes, containing:
+///
+/// - `r` (32-bytes big-endian);
+/// - `s` (32-bytes big-endian).
+/// - `v` (1-byte), the recovery id offset by `27`, ie. either `27` or
+///   `28`.
+///
+/// See also: [`SignRecoverable`].
+#[derive(near_sdk::serde::Serialize, near_sdk::serde::Deserialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(transparent)]
+pub struct SignRsv(
+    #[serde(with = "serde_big_array::BigArray")]
+    //
+    pub [u8; 65],
+);
+
+impl SignRsv {
+    /// Converts `v` into the recovery id.
+    ///
+    /// A `v` of `0` or `1` is also accepted, as some wallets don't
+    /// offset it.  
+    /// Returns `None` for any other `v`.
+    pub fn to_recoverable(&self) -> Option<SignRecoverable> {
+        let recovery_id = match self.0[64] {
+            v @ 27..=28 => v - 27,
+            v @ 0..=1 => v,
+            _ => return None,
+        };
+        let mut res = self.0;
+        res[64] = recovery_id;
+        Some(SignRecoverable(res))
+    }
+}
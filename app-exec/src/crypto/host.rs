@@ -0,0 +1,98 @@
+//! Signature verification through Near host functions.
+//!
+//! Enabled by the `host_crypto` feature. The host functions are
+//! cheaper in gas than running `k256` and `ed25519-dalek` inside of
+//! wasm, but they need a runtime that has `ecrecover` and
+//! `ed25519_verify`. Those are not exposed by the near-sdk version in
+//! use, so they are imported directly.
+//!
+//! Outside of wasm, such as on the sim tests' bindings, the software
+//! implementations are always used.
+
+/// Register that is used to read the host functions' outputs.
+const REGISTER_ID: u64 = u64::MAX - 3;
+
+mod sys {
+    extern "C" {
+        pub fn ecrecover(
+            hash_len: u64,
+            hash_ptr: u64,
+            sig_len: u64,
+            sig_ptr: u64,
+            v: u64,
+            malleability_flag: u64,
+            register_id: u64,
+        ) -> u64;
+
+        pub fn ed25519_verify(
+            sig_len: u64,
+            sig_ptr: u64,
+            msg_len: u64,
+            msg_ptr: u64,
+            pub_key_len: u64,
+            pub_key_ptr: u64,
+        ) -> u64;
+    }
+}
+
+/// Recovers the public key, without the `0x04` header, that created
+/// the `sign` (`r` and `s`) of the `msg_hash` with the recovery id
+/// `v` (`0` or `1`).
+///
+/// Signatures with a high `s` are rejected.
+///
+/// Returns `None` if the recovery fails.
+pub fn ecrecover(msg_hash: &[u8; 32], sign: &[u8; 64], v: u8) -> Option<[u8; 64]> {
+    let success = unsafe {
+        sys::ecrecover(
+            msg_hash.len() as _,
+            msg_hash.as_ptr() as _,
+            sign.len() as _,
+            sign.as_ptr() as _,
+            v as _,
+            // rejects high `s`, as k256 does
+            true as _,
+            REGISTER_ID,
+        )
+    };
+    if success == 0 {
+        return None;
+    }
+    let pubkey = near_sdk::env::read_register(REGISTER_ID)?;
+    let mut res = [0; 64];
+    res.copy_from_slice(&pubkey);
+    Some(res)
+}
+
+/// Returns `true` if `pubkey` authenticates the `sign` of the
+/// `msg_bytes`.
+pub fn ed25519_verify(pubkey: &[u8; 32], sign: &[u8; 64], msg_bytes: &[u8]) -> bool {
+    let res = unsafe {
+        sys::ed25519_verify(
+            sign.len() as _,
+            sign.as_ptr() as _,
+            msg_bytes.len() as _,
+            msg_bytes.as_ptr() as _,
+            pubkey.len() as _,
+            pubkey.as_ptr() as _,
+        )
+    };
+    res == 1
+}
+
+/// Returns `true` if `pubkey` authenticates the `sign` (`r` and `s`)
+/// of the `msg_hash`, by recovering the public key for each recovery
+/// id.
+pub fn ecdsa_secp256k1_verify(
+    pubkey: &k256::PublicKey,
+    sign: &[u8; 64],
+    msg_hash: &[u8; 32],
+) -> bool {
+    use k256::elliptic_curve::sec1::ToEncodedPoint;
+    let pubkey = pubkey.to_encoded_point(false);
+    // skips the 0x04 header
+    let pubkey = &pubkey.as_bytes()[1..];
+    (0..2).any(
+        |v| matches!(ecrecover(msg_hash, sign, v), Some(recovered) if recovered[..] == pubkey[..]),
+    )
+}
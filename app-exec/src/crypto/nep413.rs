@@ -0,0 +1,95 @@
+//! Messages signed by wallets with `signMessage`, as defined by
+//! [NEP-413](https://github.com/near/NEPs/blob/master/neps/nep-0413.md).
+
+use super::ecdsa_secp256k1::types::VerifyMode;
+use super::NearEncodedPubkey;
+use crate::{hash, Executor};
+use near_sdk::borsh::{self, BorshSerialize};
+use near_sdk::json_types::Base64VecU8;
+use near_sdk::near_bindgen;
+use near_sdk::serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::convert::TryInto;
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::ExecutorContract;
+
+/// Prefixes the payload, so that it can't be mistaken for a
+/// transaction.
+///
+/// This is `2^31 + 413`.
+pub const PAYLOAD_TAG: u32 = (1 << 31) + 413;
+
+/// The nonce of a message.
+///
+/// Has a total size of 32 bytes, and is base64 encoded on json, as
+/// the wallets have it.
+#[derive(BorshSerialize, Clone, PartialEq, Debug)]
+pub struct Nonce(pub [u8; 32]);
+
+impl Serialize for Nonce {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Serialize::serialize(&Base64VecU8(self.0.to_vec()), serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Nonce {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let bytes = Base64VecU8::deserialize(deserializer)?.0;
+        bytes
+            .try_into()
+            .map(Self)
+            .map_err(|_| de::Error::custom("the nonce must have 32 bytes"))
+    }
+}
+
+/// What gets signed, after the [`PAYLOAD_TAG`].
+#[derive(BorshSerialize)]
+pub struct Payload {
+    pub message: String,
+    pub nonce: Nonce,
+    pub recipient: String,
+    pub callback_url: Option<String>,
+}
+
+impl Payload {
+    /// The `sha256` of the borsh serialized [`PAYLOAD_TAG`] followed
+    /// by the payload.
+    pub fn hash(&self) -> hash::Sha256 {
+        let mut bytes = PAYLOAD_TAG.try_to_vec().unwrap();
+        bytes.extend(self.try_to_vec().unwrap());
+        hash::Sha256::hash_bytes(&bytes)
+    }
+}
+
+#[near_bindgen]
+impl Executor {
+    /// Verifies that `pubkey` signed the `message` with
+    /// `signMessage`, as [`verify_hashed_msg()`](Self::verify_hashed_msg)
+    /// would for the hash of the payload in the [`VerifyMode::Strict`]
+    /// mode.
+    ///
+    /// The `signature` is base64 encoded, as the wallets return it.
+    /// The `nonce`, `recipient` and `callback_url` must be the same
+    /// as the ones that were given to the wallet.
+    pub fn verify_nep413(
+        pubkey: NearEncodedPubkey,
+        signature: Base64VecU8,
+        message: String,
+        nonce: Nonce,
+        recipient: String,
+        callback_url: Option<String>,
+    ) -> bool {
+        let payload = Payload {
+            message,
+            nonce,
+            recipient,
+            callback_url,
+        };
+        Self::verify_decoded_inner(
+            &signature.0,
+            pubkey.parse(),
+            payload.hash(),
+            VerifyMode::Strict,
+        )
+    }
+}
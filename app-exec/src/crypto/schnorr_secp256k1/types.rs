@@ -0,0 +1,41 @@
+/// Public Key serialized in x-only form, as defined by BIP-340.  
+/// Only `x` is present, and `y` is implicitly the even one.
+///
+/// Has a total size of 32 bytes.
+#[derive(near_sdk::serde::Serialize, near_sdk::serde::Deserialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(transparent)]
+pub struct XOnlyPubKey(
+    #[serde(with = "serde_big_array::BigArray")]
+    //
+    pub [u8; 32],
+);
+
+impl From<k256::PublicKey> for XOnlyPubKey {
+    fn from(pubkey: k256::PublicKey) -> Self {
+        use k256::elliptic_curve::sec1::ToEncodedPoint;
+        let compress = true;
+        let pubkey = pubkey.as_affine().to_encoded_point(compress);
+        let pubkey = pubkey.as_bytes();
+        assert_eq!(pubkey.len(), 33);
+        // skips the header, which has the parity of `y`
+        let mut res = [0; 32];
+        res.copy_from_slice(&pubkey[1..33]);
+        XOnlyPubKey(res)
+    }
+}
+
+/// Signature, as defined by BIP-340.
+///
+/// Has a total size of 64 bytes, containing:
+///
+/// - `r` (32-bytes), the `x` of the nonce point.
+/// - `s` (32-bytes).
+#[derive(near_sdk::serde::Serialize, near_sdk::serde::Deserialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(transparent)]
+pub struct Sign(
+    #[serde(with = "serde_big_array::BigArray")]
+    //
+    pub [u8; 64],
+);
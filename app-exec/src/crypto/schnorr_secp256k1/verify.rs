@@ -0,0 +1,84 @@
+use super::types;
+use crate::{hash, Executor};
+use near_sdk::near_bindgen;
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::ExecutorContract;
+
+/// The tag of the challenge hash, as defined by BIP-340.
+pub const CHALLENGE_TAG: &str = "BIP0340/challenge";
+
+#[near_bindgen]
+impl Executor {
+    /// Returns `true` if `pubkey` authenticates the BIP-340
+    /// `sign` of the `msg_bytes`.  
+    /// Returns `false` otherwise.
+    ///
+    /// Differently from ecdsa, the `msg_bytes` are not hashed before
+    /// the verification, and can have any size.
+    pub fn schnorr_secp256k1_verify(
+        pubkey: types::XOnlyPubKey,
+        sign: types::Sign,
+        msg_bytes: Vec<u8>,
+    ) -> bool {
+        verify(&pubkey, &sign, &msg_bytes)
+    }
+
+    /// Returns `true` if `pubkey` authenticates the BIP-340
+    /// `sign` of the `sha256` hash of the msg.  
+    /// Returns `false` otherwise.
+    ///
+    /// The `msg_hash` must be the result of a `sha256` of the msg,
+    /// and is what gets signed.
+    pub fn schnorr_secp256k1_verify_prehashed(
+        pubkey: types::XOnlyPubKey,
+        sign: types::Sign,
+        msg_hash: hash::Sha256,
+    ) -> bool {
+        verify(&pubkey, &sign, &msg_hash.0)
+    }
+}
+
+fn verify(pubkey: &types::XOnlyPubKey, sign: &types::Sign, msg_bytes: &[u8]) -> bool {
+    use k256::elliptic_curve::group::ff::PrimeField;
+    use k256::elliptic_curve::sec1::ToEncodedPoint;
+    use k256::{FieldBytes, ProjectivePoint, Scalar};
+
+    // lift_x: the point with `x` and an even `y`
+    let pubkey_point = {
+        let mut compressed = [0x02; 33];
+        compressed[1..].copy_from_slice(&pubkey.0);
+        match k256::PublicKey::from_sec1_bytes(&compressed) {
+            Ok(pubkey) => pubkey.to_projective(),
+            Err(_) => return false,
+        }
+    };
+
+    let (r, s) = sign.0.split_at(32);
+    // `s` must be lower than the curve order.
+    // `r` is not checked against the field size as it then can't
+    // match the `x` of any point
+    let s = match Scalar::from_repr(FieldBytes::clone_from_slice(s)) {
+        Some(s) => s,
+        None => return false,
+    };
+
+    let challenge = {
+        let mut challenge = Vec::with_capacity(64 + msg_bytes.len());
+        challenge.extend(r);
+        challenge.extend(&pubkey.0);
+        challenge.extend(msg_bytes);
+        let challenge = hash::Sha256::hash_tagged(CHALLENGE_TAG, &challenge);
+        Scalar::from_bytes_reduced(FieldBytes::from_slice(&challenge.0))
+    };
+
+    // R = s⋅G - e⋅P
+    let nonce_point = ProjectivePoint::generator() * s - pubkey_point * challenge;
+
+    // R must not be the identity, must have an even `y` and its `x`
+    // must be `r`
+    let compress = true;
+    let nonce_point = nonce_point.to_affine().to_encoded_point(compress);
+    let nonce_point = nonce_point.as_bytes();
+    nonce_point.len() == 33 && nonce_point[0] == 0x02 && &nonce_point[1..] == r
+}
@@ -0,0 +1,75 @@
+use super::ecdsa_secp256k1::types::VerifyMode;
+use super::{Bs58EncodedSignature, NearEncodedPubkey};
+use crate::error::{ensure, Error};
+use crate::Executor;
+use near_sdk::near_bindgen;
+use near_sdk::serde::{Deserialize, Serialize};
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::ExecutorContract;
+
+/// Result of a [`Executor::verify_threshold()`].
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ThresholdVerification {
+    /// Whether at least `threshold` of the keys signed.
+    pub is_met: bool,
+    /// The keys that signed the `msg`, in the same order as they
+    /// were given.
+    pub signers: Vec<NearEncodedPubkey>,
+}
+
+#[near_bindgen]
+impl Executor {
+    /// Verifies that at least `threshold` of the `pubkeys` signed
+    /// the `msg`, as [`verify_msg()`](Self::verify_msg) would for each
+    /// of them in the [`VerifyMode::Strict`] mode.
+    ///
+    /// `signatures` must have the same length as `pubkeys`, where
+    /// each signature is from the key at the same position, or
+    /// `null` if that key didn't sign.
+    /// The keys can be of different curves, but the same key can't
+    /// be given more than once.
+    ///
+    /// A malformed signature only counts as that key not having
+    /// signed, as [`try_verify_msg()`](Self::try_verify_msg) would
+    /// report it.
+    pub fn verify_threshold(
+        msg: String,
+        signatures: Vec<Option<Bs58EncodedSignature>>,
+        pubkeys: Vec<NearEncodedPubkey>,
+        threshold: u64,
+    ) -> ThresholdVerification {
+        ensure(
+            signatures.len() == pubkeys.len(),
+            Error::ThresholdLenMismatch,
+        );
+        ensure(
+            threshold != 0 && threshold <= pubkeys.len() as u64,
+            Error::InvalidThreshold,
+        );
+
+        let parsed: Vec<near_sdk::PublicKey> = pubkeys.iter().map(|p| p.parse()).collect();
+        for (i, pubkey) in parsed.iter().enumerate() {
+            ensure(!parsed[..i].contains(pubkey), Error::DuplicateSigner);
+        }
+
+        let msg_hash = crate::hash::Sha256::hash_bytes(msg.as_bytes());
+        let signers: Vec<NearEncodedPubkey> = signatures
+            .into_iter()
+            .zip(parsed)
+            .zip(pubkeys)
+            .filter_map(|((sign, parsed), pubkey)| {
+                let sign = sign?;
+                Self::try_verify_inner(sign, parsed, msg_hash.clone(), VerifyMode::Strict)
+                    .unwrap_or(false)
+                    .then(|| pubkey)
+            })
+            .collect();
+
+        ThresholdVerification {
+            is_met: signers.len() as u64 >= threshold,
+            signers,
+        }
+    }
+}
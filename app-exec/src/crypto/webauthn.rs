@@ -0,0 +1,6 @@
+//! Passkey assertions, as defined by
+//! [WebAuthn](https://www.w3.org/TR/webauthn-2/#sctn-verifying-assertion),
+//! with `ecdsa` on `secp256r1` (COSE algorithm `-7`).
+
+pub mod types;
+pub mod verify;
@@ -0,0 +1,39 @@
+use near_sdk::serde::{Deserialize, Serialize};
+
+/// The `UP` flag, set when the user was present.
+pub const FLAG_USER_PRESENT: u8 = 0x01;
+/// The `UV` flag, set when the user was verified, eg. by a pin or
+/// a biometric.
+pub const FLAG_USER_VERIFIED: u8 = 0x04;
+
+/// The `type` of the client data of an assertion.
+pub const CLIENT_DATA_TYPE_GET: &str = "webauthn.get";
+
+/// The minimum size of the authenticator data: the `rpIdHash`
+/// (32-bytes), the `flags` (1-byte) and the `signCount` (4-bytes).
+pub const AUTHENTICATOR_DATA_MIN_LEN: usize = 32 + 1 + 4;
+
+/// The response of a `navigator.credentials.get()`.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Assertion {
+    /// The raw `authenticatorData`.
+    #[serde(alias = "authenticatorData")]
+    pub authenticator_data: Vec<u8>,
+    /// The `clientDataJSON`, as it was signed.
+    #[serde(alias = "clientDataJSON")]
+    pub client_data_json: String,
+    /// The `signature`, DER encoded.
+    pub signature: Vec<u8>,
+}
+
+/// The fields of the `clientDataJSON` that are checked.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ClientData {
+    #[serde(rename = "type")]
+    pub type_: String,
+    /// The challenge, base64url encoded without padding.
+    pub challenge: String,
+    pub origin: String,
+}
@@ -0,0 +1,75 @@
+use super::types;
+use crate::crypto::{der, ecdsa_secp256r1 as r1};
+use crate::error::{Error, OrPanicStr};
+use crate::{hash, Executor};
+use near_sdk::near_bindgen;
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::ExecutorContract;
+
+#[near_bindgen]
+impl Executor {
+    /// Returns `true` if `pubkey` authenticates the `assertion`,
+    /// made for the relying party `rp_id` from a page on `origin`
+    /// over the `challenge`.  
+    /// Returns `false` otherwise.
+    ///
+    /// The `rpIdHash` must be the `sha256` of `rp_id`, the user must
+    /// have been present and, if `require_user_verification`, also
+    /// verified.
+    /// The `clientDataJSON` must be of an assertion, have the
+    /// base64url of the `challenge` and have exactly the `origin`.
+    /// The signature is of the `sha256` of the `authenticatorData`
+    /// followed by the `sha256` of the `clientDataJSON`.
+    pub fn webauthn_verify(
+        pubkey: r1::types::PubKeyUncompressed,
+        assertion: types::Assertion,
+        rp_id: String,
+        origin: String,
+        challenge: Vec<u8>,
+        require_user_verification: bool,
+    ) -> bool {
+        let auth_data = &assertion.authenticator_data;
+        if auth_data.len() < types::AUTHENTICATOR_DATA_MIN_LEN {
+            Error::BadAuthenticatorData.panic();
+        }
+        let rp_id_hash = &auth_data[0..32];
+        let flags = auth_data[32];
+
+        if rp_id_hash != hash::Sha256::hash_bytes(rp_id.as_bytes()).0 {
+            return false;
+        }
+        if flags & types::FLAG_USER_PRESENT == 0 {
+            return false;
+        }
+        if require_user_verification && flags & types::FLAG_USER_VERIFIED == 0 {
+            return false;
+        }
+
+        let client_data: types::ClientData =
+            near_sdk::serde_json::from_str(&assertion.client_data_json)
+                .or_panic_str(Error::BadClientData);
+        if client_data.type_ != types::CLIENT_DATA_TYPE_GET {
+            return false;
+        }
+        let challenge = {
+            use near_sdk::base64;
+            base64::encode_config(&challenge, base64::URL_SAFE_NO_PAD)
+        };
+        if client_data.challenge != challenge {
+            return false;
+        }
+        // as the page that asked for the assertion could be of
+        // another site that shares the rp_id
+        if client_data.origin != origin {
+            return false;
+        }
+
+        let sign =
+            der::parse_ecdsa_signature(&assertion.signature).or_panic_str(Error::BadDerSignature);
+
+        let mut signed = auth_data.clone();
+        signed.extend(&hash::Sha256::hash_bytes(assertion.client_data_json.as_bytes()).0);
+        Self::ecdsa_secp256r1_verify(&pubkey.0, r1::types::SignCompact(sign), &signed)
+    }
+}
@@ -1,3 +1,4 @@
+use near_sdk::serde::{Deserialize, Serialize};
 use strum_macros::Display;
 
 /// User-facing errors.
@@ -10,10 +11,14 @@ use strum_macros::Display;
 /// indicate an internal bug, then `[panic!()]` should be used
 /// as it prints line code information that would be useful for
 /// debugging and fixing the problem.
-#[derive(Debug, Display)]
+#[derive(Debug, Display, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
 pub enum Error {
     #[strum(serialize = "ERR_EXEC_ALREADY_INITIALIZED")]
     AlreadyInitialized,
+    /// Tried to migrate a contract that has no state.
+    #[strum(serialize = "ERR_EXEC_NOT_INITIALIZED")]
+    NotInitialized,
     /// A call that was supposed to be made by the owner was made
     /// by a different predecessor.
     #[strum(serialize = "ERR_EXEC_NOT_OWNER")]
@@ -28,6 +33,108 @@ pub enum Error {
     /// added instead.
     #[strum(serialize = "ERR_EXEC_CALL_CURRENT")]
     CallCurrentAccount,
+    /// Tried to add a relayer key that was already added.
+    #[strum(serialize = "ERR_EXEC_RELAYER_KEY_ALREADY_ADDED")]
+    RelayerKeyAlreadyAdded,
+    /// Tried to remove a relayer key that wasn't added.
+    #[strum(serialize = "ERR_EXEC_RELAYER_KEY_MISSING")]
+    MissingRelayerKey,
+    /// Tried to add a relayer key with a zero allowance, which would
+    /// be unlimited.
+    #[strum(serialize = "ERR_EXEC_RELAYER_KEY_ZERO_ALLOWANCE")]
+    ZeroAllowance,
+    /// The deposit would exceed the user's lifetime spending cap
+    /// on the app.
+    #[strum(serialize = "ERR_EXEC_LIFETIME_SPENDING_CAP_EXCEEDED")]
+    LifetimeSpendingCapExceeded,
+    /// The deposit would exceed the user's daily spending cap on the
+    /// app.
+    #[strum(serialize = "ERR_EXEC_DAILY_SPENDING_CAP_EXCEEDED")]
+    DailySpendingCapExceeded,
+    /// Tried to get an audit log entry past the end of the log.
+    #[strum(serialize = "ERR_EXEC_AUDIT_ENTRY_MISSING")]
+    MissingAuditEntry,
+    /// A public key or a signature is not valid base58.
+    #[strum(serialize = "ERR_EXEC_BAD_BASE58")]
+    BadBase58,
+    /// A public key or a signature is not valid base64.
+    #[strum(serialize = "ERR_EXEC_BAD_BASE64")]
+    BadBase64,
+    /// A public key or a signature is not valid hex.
+    #[strum(serialize = "ERR_EXEC_BAD_HEX")]
+    BadHex,
+    /// The `{header}:` prefix of a public key is not one of the
+    /// curves that Near supports, or the key is not on the curve that
+    /// the method expects.
+    #[strum(serialize = "ERR_EXEC_UNKNOWN_CURVE")]
+    UnknownCurve,
+    /// The public key doesn't have the size expected for its curve.
+    #[strum(serialize = "ERR_EXEC_WRONG_PUBKEY_LENGTH")]
+    WrongPubkeyLength,
+    /// The signature doesn't have the size expected for the curve
+    /// of the public key.
+    #[strum(serialize = "ERR_EXEC_WRONG_SIGNATURE_LENGTH")]
+    WrongSignatureLength,
+    /// The public key is not a point on its curve.
+    #[strum(serialize = "ERR_EXEC_INVALID_POINT")]
+    InvalidPoint,
+    /// The signature is not in its canonical encoding, eg. a
+    /// secp256k1 signature in the higher-S form or with an invalid
+    /// recovery id.
+    #[strum(serialize = "ERR_EXEC_NON_CANONICAL_SIGNATURE")]
+    NonCanonicalSignature,
+    /// The signing algorithm is not for the curve of the public key.
+    #[strum(serialize = "ERR_EXEC_WRONG_ALGORITHM")]
+    WrongAlgorithm,
+    /// The context of an `Ed25519ph` or `Ed25519ctx` signature is
+    /// longer than 255 bytes, or is empty for `Ed25519ctx`.
+    #[strum(serialize = "ERR_EXEC_BAD_CONTEXT")]
+    BadContext,
+    /// The recoverable signature is malformed, so it doesn't recover
+    /// into any public key.
+    #[strum(serialize = "ERR_EXEC_RECOVER_FAILED")]
+    RecoverFailed,
+    /// An Ethereum address is not `0x`-prefixed hex of 20 bytes, or
+    /// its mixed-case checksum doesn't match.
+    #[strum(serialize = "ERR_EXEC_ETH_BAD_ADDRESS")]
+    BadEthAddress,
+    /// An EIP-712 struct references a type that is not defined.
+    #[strum(serialize = "ERR_EXEC_EIP712_MISSING_TYPE")]
+    Eip712MissingType,
+    /// An EIP-712 value is missing a field of its struct type.
+    #[strum(serialize = "ERR_EXEC_EIP712_MISSING_FIELD")]
+    Eip712MissingField,
+    /// An EIP-712 field has a type that is neither a struct type nor
+    /// an atomic or dynamic type of EIP-712, eg. `uint7`.
+    #[strum(serialize = "ERR_EXEC_EIP712_UNKNOWN_TYPE")]
+    Eip712UnknownType,
+    /// An EIP-712 value doesn't match its type, eg. a negative or
+    /// out-of-range `uint8`.
+    #[strum(serialize = "ERR_EXEC_EIP712_BAD_VALUE")]
+    Eip712BadValue,
+    /// A threshold verification has a different number of
+    /// signatures and of public keys.
+    #[strum(serialize = "ERR_EXEC_THRESHOLD_LEN_MISMATCH")]
+    ThresholdLenMismatch,
+    /// The threshold is zero or greater than the number of public
+    /// keys.
+    #[strum(serialize = "ERR_EXEC_THRESHOLD_INVALID")]
+    InvalidThreshold,
+    /// A threshold verification has the same public key more than
+    /// once.
+    #[strum(serialize = "ERR_EXEC_THRESHOLD_DUPLICATE_SIGNER")]
+    DuplicateSigner,
+    /// A signature is not in the DER encoding, eg. of a WebAuthn
+    /// assertion.
+    #[strum(serialize = "ERR_EXEC_BAD_DER_SIGNATURE")]
+    BadDerSignature,
+    /// The WebAuthn authenticator data is shorter than its fixed
+    /// fields.
+    #[strum(serialize = "ERR_EXEC_WEBAUTHN_BAD_AUTHENTICATOR_DATA")]
+    BadAuthenticatorData,
+    /// The WebAuthn `clientDataJSON` is not json, or misses a field.
+    #[strum(serialize = "ERR_EXEC_WEBAUTHN_BAD_CLIENT_DATA")]
+    BadClientData,
 }
 
 impl Error {
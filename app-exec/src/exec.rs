@@ -1,10 +1,14 @@
 #![allow(clippy::let_and_return)]
 
-use crate::error::{ensure, Error};
+use crate::audit::AuditCall;
+use crate::error::Error;
 use crate::Executor;
-use near_sdk::json_types::U64;
+use near_sdk::json_types::{U128, U64};
 use near_sdk::serde::{Deserialize, Serialize};
-use near_sdk::{env, ext_contract, near_bindgen, serde_json, AccountId, Promise, PromiseResult};
+use near_sdk::{
+    env, ext_contract, near_bindgen, serde_json, AccountId, Balance, Promise, PromiseResult,
+    PublicKey,
+};
 
 #[cfg(not(target_arch = "wasm32"))]
 use crate::ExecutorContract;
@@ -15,10 +19,14 @@ pub trait ExtSelf {
     /// and forwarding the calls result back.
     ///
     /// Only forwards the first result.
-    fn check_promise(tag_info: TagInfo) -> Vec<u8>;
+    fn check_promise(tag_info: TagInfo, track_user: bool, audit: AuditCall) -> Vec<u8>;
+
+    /// Fails with the error of a contract call that was forwarded
+    /// by [`Executor::check_promise()`].
+    fn fail_promise();
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 #[serde(crate = "near_sdk::serde")]
 pub struct ContractCall {
     pub contract_id: AccountId,
@@ -26,7 +34,7 @@ pub struct ContractCall {
     pub args: String,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 #[serde(crate = "near_sdk::serde")]
 pub struct CallContext {
     pub contract_call: ContractCall,
@@ -37,7 +45,7 @@ pub struct CallContext {
     // pub signature: crate::crypto::Bs58EncodedSignature,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 #[serde(crate = "near_sdk::serde")]
 pub struct TagInfo {
     pub app_id: String,
@@ -51,42 +59,136 @@ impl Executor {
     /// and forwarding the calls result back.
     ///
     /// Only forwards the first result.
+    ///
+    /// If the contract call fails, the execution still fails with
+    /// `Promise with index 0 failed`, but from a
+    /// [`Self::fail_promise()`] receipt that follows
+    /// [`Self::check_promise()`], so that the failure stays recorded
+    /// on the statistics.
     #[payable]
     pub fn execute(&mut self, context: CallContext) -> Promise {
-        self.assert_owner();
+        let caller = env::predecessor_account_id();
+        let signer_key = env::signer_account_pk();
+        let deposit = env::attached_deposit();
+        if let Some(error) = self
+            .check_execution(&context, &caller, Some(&signer_key), deposit)
+            .first()
+        {
+            error.panic()
+        }
 
-        // makes sure it won't call an internal private function
-        ensure(
-            context.contract_call.contract_id != env::current_account_id(),
-            Error::CallCurrentAccount,
-        );
+        self.record_spending(&context.tag_info, deposit);
+        self.record_execution_start(&context.tag_info, deposit);
+        // the result is recorded for the same stats as the start, even
+        // if the user tracking changes in between
+        let track_user = self.track_user_stats;
+        let audit = AuditCall::new(&context, deposit);
 
         Promise::new(context.contract_call.contract_id)
             .function_call(
                 context.contract_call.method_name,
                 context.contract_call.args.as_bytes().to_vec(),
-                env::attached_deposit(),
+                deposit,
                 env::prepaid_gas() / 3,
             )
             .then(ext_self::check_promise(
                 context.tag_info,
+                track_user,
+                audit,
                 env::current_account_id(),
                 0,
                 env::prepaid_gas() / 3,
             ))
     }
 
+    /// Runs the same checks that [`Self::execute`] would make if
+    /// `caller` were to execute `context` with `deposit` attached
+    /// (defaults to `0`), without creating any promise.
+    ///
+    /// When `caller` is the executor itself, `relayer_key` is the
+    /// access key that would sign the call, which must be a relayer
+    /// key.
+    ///
+    /// Returns the violations, in the order that they are checked, as
+    /// [`Error`] variant names.  
+    /// An empty list means that the execution would be accepted.
+    pub fn validate_execution(
+        &self,
+        context: CallContext,
+        caller: AccountId,
+        deposit: Option<U128>,
+        relayer_key: Option<PublicKey>,
+    ) -> Vec<Error> {
+        let deposit = deposit.map(|d| d.0).unwrap_or_default();
+        self.check_execution(&context, &caller, relayer_key.as_ref(), deposit)
+    }
+
     /// Checks the first result of an external call that was made,
     /// forwarding the first promise result as the value result.
     ///
-    /// Logs on successful promise.
+    /// Logs on successful promise.  
+    /// Records the result on the execution statistics, including the
+    /// user's if `track_user` was enabled when the execution started,
+    /// and appends the execution into the audit log.
+    #[private]
+    pub fn check_promise(&mut self, tag_info: TagInfo, track_user: bool, audit: AuditCall) {
+        match env::promise_result(0) {
+            PromiseResult::Successful(ret) => {
+                self.record_execution_result(&tag_info, track_user, true);
+                self.record_audit_entry(&tag_info, audit, true);
+                env::log_str(&serde_json::to_string(&tag_info).unwrap());
+                env::value_return(&ret);
+            }
+            _ => {
+                // the deposit was refunded, so it doesn't count
+                // towards the spending cap
+                self.revert_spending(&tag_info, audit.deposit.0, audit.timestamp.0);
+                self.record_execution_result(&tag_info, track_user, false);
+                self.record_audit_entry(&tag_info, audit, false);
+                // panicking here would also revert the statistics,
+                // so the failure is returned from another receipt
+                ext_self::fail_promise(env::current_account_id(), 0, env::prepaid_gas() / 3)
+                    .as_return();
+            }
+        }
+    }
+
+    /// Fails with the error of a contract call that was forwarded
+    /// by [`Self::check_promise()`].
     #[private]
-    pub fn check_promise(tag_info: TagInfo) {
-        let ret = match env::promise_result(0) {
-            PromiseResult::Successful(val) => val,
-            _ => env::panic_str("Promise with index 0 failed"),
-        };
-        env::log_str(&serde_json::to_string(&tag_info).unwrap());
-        env::value_return(&ret);
+    pub fn fail_promise() {
+        env::panic_str("Promise with index 0 failed")
+    }
+}
+
+impl Executor {
+    /// Collects every static check violation of `caller`, signing
+    /// with `signer_key`, executing `context` with `deposit` attached.
+    pub(crate) fn check_execution(
+        &self,
+        context: &CallContext,
+        caller: &AccountId,
+        signer_key: Option<&PublicKey>,
+        deposit: Balance,
+    ) -> Vec<Error> {
+        let mut violations = vec![];
+
+        // the executor itself is the caller when using a relayer key,
+        // but also when using any other of its own keys, which are
+        // not accepted
+        let is_relayer = caller == &env::current_account_id()
+            && signer_key.map_or(false, |key| self.relayer_keys.get(key).is_some());
+        if !self.owner_ids.contains(caller) && !is_relayer {
+            violations.push(Error::NotOwner);
+        }
+
+        // makes sure it won't call an internal private function
+        if context.contract_call.contract_id == env::current_account_id() {
+            violations.push(Error::CallCurrentAccount);
+        }
+
+        violations.extend(self.check_spending(&context.tag_info, deposit));
+
+        violations
     }
 }
@@ -1,14 +1,24 @@
 use crate::Executor;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::{env, near_bindgen};
 
 #[cfg(not(target_arch = "wasm32"))]
 use crate::ExecutorContract;
 
+pub mod merkle;
+
 /// Sha256 value.
 ///
 /// Has a total size of 32 bytes.
 #[derive(
-    near_sdk::serde::Serialize, near_sdk::serde::Deserialize, Clone, PartialEq, Debug, Default,
+    near_sdk::serde::Serialize,
+    near_sdk::serde::Deserialize,
+    BorshDeserialize,
+    BorshSerialize,
+    Clone,
+    PartialEq,
+    Debug,
+    Default,
 )]
 #[serde(crate = "near_sdk::serde")]
 #[serde(transparent)]
@@ -29,6 +39,19 @@ impl Sha256 {
         res.copy_from_slice(hash);
         Sha256(res)
     }
+
+    /// The `sha256` of `msg_bytes` with a domain separation `tag`, as
+    /// defined by BIP-340.
+    ///
+    /// This is `sha256(sha256(tag) || sha256(tag) || msg_bytes)`.
+    pub fn hash_tagged(tag: &str, msg_bytes: &[u8]) -> Self {
+        let tag_hash = Self::hash_bytes(tag.as_bytes());
+        let mut bytes = Vec::with_capacity(64 + msg_bytes.len());
+        bytes.extend(&tag_hash.0);
+        bytes.extend(&tag_hash.0);
+        bytes.extend(msg_bytes);
+        Self::hash_bytes(&bytes)
+    }
 }
 
 // needed so this is a Digest
@@ -123,6 +146,142 @@ impl digest::FixedOutputDirty for Sha512 {
     }
 }
 
+/// Keccak256 value.
+///
+/// This is the original Keccak submission, as used by Ethereum, which
+/// has a different padding from the standardized `sha3-256`.
+///
+/// Has a total size of 32 bytes.
+#[derive(
+    near_sdk::serde::Serialize, near_sdk::serde::Deserialize, Clone, PartialEq, Debug, Default,
+)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(transparent)]
+pub struct Keccak256(
+    #[serde(with = "serde_big_array::BigArray")]
+    //
+    pub [u8; 32],
+);
+
+impl Keccak256 {
+    /// Uses the host function on-chain, and otherwise doesn't depend
+    /// on the Near runtime, so it can also be used off-chain.
+    pub fn hash_bytes(msg_bytes: &[u8]) -> Self {
+        #[cfg(target_arch = "wasm32")]
+        let hash = near_sdk::env::keccak256(msg_bytes);
+        #[cfg(not(target_arch = "wasm32"))]
+        let hash = {
+            use sha3::Digest;
+            sha3::Keccak256::digest(msg_bytes)
+        };
+        let hash = hash.as_slice();
+        assert_eq!(hash.len(), 32);
+        let mut res = [0u8; 32];
+        res.copy_from_slice(hash);
+        Keccak256(res)
+    }
+}
+
+/// Sha3-256 value.
+///
+/// This is the standardized `sha3-256`, which has a different padding
+/// from the original Keccak submission.
+///
+/// Has a total size of 32 bytes.
+#[derive(
+    near_sdk::serde::Serialize, near_sdk::serde::Deserialize, Clone, PartialEq, Debug, Default,
+)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(transparent)]
+pub struct Sha3_256(
+    #[serde(with = "serde_big_array::BigArray")]
+    //
+    pub [u8; 32],
+);
+
+impl Sha3_256 {
+    pub fn hash_bytes(msg_bytes: &[u8]) -> Self {
+        // there is no host function for sha3-256
+        use sha3::Digest;
+        let mut hash = sha3::Sha3_256::new();
+        hash.update(msg_bytes);
+        let hash = hash.finalize();
+        let mut res = [0u8; 32];
+        res.copy_from_slice(hash.as_slice());
+        Sha3_256(res)
+    }
+}
+
+/// Ripemd160 value.
+///
+/// Has a total size of 20 bytes.
+#[derive(
+    near_sdk::serde::Serialize, near_sdk::serde::Deserialize, Clone, PartialEq, Debug, Default,
+)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(transparent)]
+pub struct Ripemd160(pub [u8; 20]);
+
+impl Ripemd160 {
+    pub fn hash_bytes(msg_bytes: &[u8]) -> Self {
+        // the ripemd160 host function is not exposed by this near-sdk
+        // version
+        use ripemd160::Digest;
+        let mut hash = ripemd160::Ripemd160::new();
+        hash.update(msg_bytes);
+        let hash = hash.finalize();
+        let mut res = [0u8; 20];
+        res.copy_from_slice(hash.as_slice());
+        Ripemd160(res)
+    }
+}
+
+/// Blake2b value, with an output size of 256 bits.
+///
+/// Has a total size of 32 bytes.
+#[derive(
+    near_sdk::serde::Serialize, near_sdk::serde::Deserialize, Clone, PartialEq, Debug, Default,
+)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(transparent)]
+pub struct Blake2b256(
+    #[serde(with = "serde_big_array::BigArray")]
+    //
+    pub [u8; 32],
+);
+
+impl Blake2b256 {
+    pub fn hash_bytes(msg_bytes: &[u8]) -> Self {
+        // there is no host function for blake2b
+        use blake2::digest::{Update, VariableOutput};
+        let mut hash = blake2::VarBlake2b::new(32).unwrap();
+        hash.update(msg_bytes);
+        let mut res = [0u8; 32];
+        hash.finalize_variable(|hash| res.copy_from_slice(hash));
+        Blake2b256(res)
+    }
+}
+
+/// Hash160 value, as used by Bitcoin addresses.
+///
+/// This is the `ripemd160` of the `sha256` of the message.
+///
+/// Has a total size of 20 bytes.
+#[derive(
+    near_sdk::serde::Serialize, near_sdk::serde::Deserialize, Clone, PartialEq, Debug, Default,
+)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(transparent)]
+pub struct Hash160(pub [u8; 20]);
+
+impl Hash160 {
+    pub fn hash_bytes(msg_bytes: &[u8]) -> Self {
+        let sha256 = Sha256::hash_bytes(msg_bytes);
+        let Ripemd160(res) = Ripemd160::hash_bytes(&sha256.0);
+        Hash160(res)
+    }
+}
+
 #[near_bindgen]
 impl Executor {
     /// Generates a `sha256` hash of the given bytes.
@@ -172,4 +331,101 @@ impl Executor {
     pub fn hash_sha512_msg(msg: String) -> Sha512 {
         Sha512::hash_bytes(msg.as_bytes())
     }
+
+    /// Generates a `keccak256` hash of the given bytes.
+    ///
+    /// The returned hash has a total size of 32-bytes.
+    ///
+    /// See also: [`Self::hash_keccak256_msg`]
+    pub fn hash_keccak256(msg_bytes: Vec<u8>) -> Keccak256 {
+        Keccak256::hash_bytes(&msg_bytes)
+    }
+
+    /// Generates a `keccak256` hash of the byte-repesentation of the
+    /// given `msg`.
+    ///
+    /// The returned hash has a total size of 32-bytes.
+    ///
+    /// See also: [`Self::hash_keccak256`]
+    pub fn hash_keccak256_msg(msg: String) -> Keccak256 {
+        Keccak256::hash_bytes(msg.as_bytes())
+    }
+
+    /// Generates a `sha3-256` hash of the given bytes.
+    ///
+    /// The returned hash has a total size of 32-bytes.
+    ///
+    /// See also: [`Self::hash_sha3_256_msg`]
+    pub fn hash_sha3_256(msg_bytes: Vec<u8>) -> Sha3_256 {
+        Sha3_256::hash_bytes(&msg_bytes)
+    }
+
+    /// Generates a `sha3-256` hash of the byte-repesentation of the
+    /// given `msg`.
+    ///
+    /// The returned hash has a total size of 32-bytes.
+    ///
+    /// See also: [`Self::hash_sha3_256`]
+    pub fn hash_sha3_256_msg(msg: String) -> Sha3_256 {
+        Sha3_256::hash_bytes(msg.as_bytes())
+    }
+
+    /// Generates a `ripemd160` hash of the given bytes.
+    ///
+    /// The returned hash has a total size of 20-bytes.
+    ///
+    /// See also: [`Self::hash_ripemd160_msg`]
+    pub fn hash_ripemd160(msg_bytes: Vec<u8>) -> Ripemd160 {
+        Ripemd160::hash_bytes(&msg_bytes)
+    }
+
+    /// Generates a `ripemd160` hash of the byte-repesentation of the
+    /// given `msg`.
+    ///
+    /// The returned hash has a total size of 20-bytes.
+    ///
+    /// See also: [`Self::hash_ripemd160`]
+    pub fn hash_ripemd160_msg(msg: String) -> Ripemd160 {
+        Ripemd160::hash_bytes(msg.as_bytes())
+    }
+
+    /// Generates a `blake2b` hash, with a 256-bit output, of the given
+    /// bytes.
+    ///
+    /// The returned hash has a total size of 32-bytes.
+    ///
+    /// See also: [`Self::hash_blake2b256_msg`]
+    pub fn hash_blake2b256(msg_bytes: Vec<u8>) -> Blake2b256 {
+        Blake2b256::hash_bytes(&msg_bytes)
+    }
+
+    /// Generates a `blake2b` hash, with a 256-bit output, of the
+    /// byte-repesentation of the given `msg`.
+    ///
+    /// The returned hash has a total size of 32-bytes.
+    ///
+    /// See also: [`Self::hash_blake2b256`]
+    pub fn hash_blake2b256_msg(msg: String) -> Blake2b256 {
+        Blake2b256::hash_bytes(msg.as_bytes())
+    }
+
+    /// Generates a `hash160` hash, ie. `ripemd160(sha256(msg_bytes))`,
+    /// of the given bytes.
+    ///
+    /// The returned hash has a total size of 20-bytes.
+    ///
+    /// See also: [`Self::hash_hash160_msg`]
+    pub fn hash_hash160(msg_bytes: Vec<u8>) -> Hash160 {
+        Hash160::hash_bytes(&msg_bytes)
+    }
+
+    /// Generates a `hash160` hash, ie. `ripemd160(sha256(msg))`, of
+    /// the byte-repesentation of the given `msg`.
+    ///
+    /// The returned hash has a total size of 20-bytes.
+    ///
+    /// See also: [`Self::hash_hash160`]
+    pub fn hash_hash160_msg(msg: String) -> Hash160 {
+        Hash160::hash_bytes(msg.as_bytes())
+    }
 }
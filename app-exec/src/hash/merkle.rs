@@ -0,0 +1,173 @@
+//! Merkle trees, for checking that a leaf is in a set that is only
+//! committed to by its root.
+//!
+//! The proofs don't depend on the Near runtime, so they can also be
+//! verified off-chain. The trees are built host-side, by the cli,
+//! except for the audit log's, which is built by the executor.
+
+use crate::hash::{Keccak256, Sha256};
+use crate::Executor;
+use near_sdk::near_bindgen;
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::ExecutorContract;
+
+/// A node of a Merkle tree, which is either a leaf or a root.
+///
+/// Has a total size of 32 bytes.
+#[derive(
+    near_sdk::serde::Serialize,
+    near_sdk::serde::Deserialize,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Debug,
+    Default,
+)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(transparent)]
+pub struct MerkleNode(
+    #[serde(with = "serde_big_array::BigArray")]
+    //
+    pub [u8; 32],
+);
+
+impl From<Sha256> for MerkleNode {
+    fn from(hash: Sha256) -> Self {
+        Self(hash.0)
+    }
+}
+
+impl From<Keccak256> for MerkleNode {
+    fn from(hash: Keccak256) -> Self {
+        Self(hash.0)
+    }
+}
+
+/// The hash that joins the nodes.
+#[derive(
+    near_sdk::serde::Serialize, near_sdk::serde::Deserialize, Clone, Copy, PartialEq, Debug,
+)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(rename_all = "snake_case")]
+pub enum MerkleHash {
+    Sha256,
+    /// As used by Ethereum, eg. by OpenZeppelin's `MerkleProof`.
+    Keccak256,
+}
+
+impl MerkleHash {
+    /// Hashes `data` into a leaf.
+    pub fn hash_leaf(&self, data: &[u8]) -> MerkleNode {
+        match self {
+            MerkleHash::Sha256 => Sha256::hash_bytes(data).into(),
+            MerkleHash::Keccak256 => Keccak256::hash_bytes(data).into(),
+        }
+    }
+
+    /// The parent of `left` and `right`, which is the hash of their
+    /// concatenation.
+    pub fn hash_pair(&self, left: &MerkleNode, right: &MerkleNode) -> MerkleNode {
+        let mut bytes = [0u8; 64];
+        bytes[..32].copy_from_slice(&left.0);
+        bytes[32..].copy_from_slice(&right.0);
+        self.hash_leaf(&bytes)
+    }
+
+    /// The parent of `a` and `b`, in whichever order, which is the
+    /// hash of the concatenation of the lower and then the higher of
+    /// them.
+    pub fn hash_sorted_pair(&self, a: &MerkleNode, b: &MerkleNode) -> MerkleNode {
+        if a <= b {
+            self.hash_pair(a, b)
+        } else {
+            self.hash_pair(b, a)
+        }
+    }
+}
+
+/// How the nodes are paired into their parents.
+#[derive(near_sdk::serde::Serialize, near_sdk::serde::Deserialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(rename_all = "snake_case")]
+pub enum Pairing {
+    /// Each pair is sorted before being hashed, so the proof doesn't
+    /// need the position of the leaf.
+    ///
+    /// This is the format of OpenZeppelin's `MerkleProof`.
+    Sorted,
+    /// The position of the leaf, whose bits, from the lowest, tell
+    /// whether each sibling is on the right (`0`) or on the left
+    /// (`1`).
+    ///
+    /// This proves the leaf is at that position.
+    Indexed(u32),
+}
+
+/// Proof that a leaf is in the tree of some root.
+///
+/// See [`verify_proof()`].
+#[derive(near_sdk::serde::Serialize, near_sdk::serde::Deserialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MerkleProof {
+    pub hash: MerkleHash,
+    pub pairing: Pairing,
+    /// The siblings from the leaf up to the root, which is not
+    /// included.
+    pub siblings: Vec<MerkleNode>,
+}
+
+impl MerkleProof {
+    /// The root that results from joining `leaf` with each of the
+    /// siblings.
+    ///
+    /// Is `None` if the index of an [`Pairing::Indexed`] proof is out
+    /// of the bounds of the tree.
+    pub fn root(&self, leaf: &MerkleNode) -> Option<MerkleNode> {
+        let hash = self.hash;
+        let siblings = self.siblings.iter();
+        match self.pairing {
+            Pairing::Sorted => Some(siblings.fold(leaf.clone(), |node, sibling| {
+                hash.hash_sorted_pair(&node, sibling)
+            })),
+            Pairing::Indexed(index) => {
+                // otherwise many indexes would have the same proof
+                let height = self.siblings.len() as u32;
+                if height > 32 || index.checked_shr(height).unwrap_or(0) != 0 {
+                    return None;
+                }
+                Some(
+                    siblings
+                        .enumerate()
+                        .fold(leaf.clone(), |node, (level, sibling)| {
+                            if (index >> level) & 1 == 0 {
+                                hash.hash_pair(&node, sibling)
+                            } else {
+                                hash.hash_pair(sibling, &node)
+                            }
+                        }),
+                )
+            }
+        }
+    }
+}
+
+/// Checks that `proof` joins `leaf` up into `root`.
+///
+/// This doesn't depend on the Near runtime, so it can also be used
+/// off-chain.
+pub fn verify_proof(root: &MerkleNode, leaf: &MerkleNode, proof: &MerkleProof) -> bool {
+    proof.root(leaf).as_ref() == Some(root)
+}
+
+#[near_bindgen]
+impl Executor {
+    /// Verifies that `leaf` is in the Merkle tree of `root`.
+    ///
+    /// See also: [`verify_proof()`].
+    pub fn verify_merkle_proof(root: MerkleNode, leaf: MerkleNode, proof: MerkleProof) -> bool {
+        verify_proof(&root, &leaf, &proof)
+    }
+}
@@ -1,23 +1,52 @@
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use near_sdk::collections::UnorderedSet;
-use near_sdk::{env, near_bindgen, AccountId, BorshStorageKey, PanicOnDefault};
+use near_sdk::collections::{LookupMap, UnorderedMap, UnorderedSet, Vector};
+use near_sdk::{env, near_bindgen, AccountId, BorshStorageKey, PanicOnDefault, PublicKey};
 
+pub mod audit;
 pub mod crypto;
 pub mod error;
 pub mod exec;
 pub mod hash;
+pub mod relayer;
+pub mod spending;
+pub mod stats;
 
-use error::{ensure, Error};
+use error::{ensure, Error, OrPanicStr};
+
+/// The most entries that a paginated view returns at once.
+pub const MAX_PAGE_LIMIT: u64 = 100;
 
 #[near_bindgen]
 #[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
 pub struct Executor {
     owner_ids: UnorderedSet<AccountId>,
+    app_stats: UnorderedMap<String, stats::ExecutionStats>,
+    user_stats: UnorderedMap<stats::AppUser, stats::ExecutionStats>,
+    track_user_stats: bool,
+    relayer_keys: UnorderedMap<PublicKey, relayer::RelayerKey>,
+    spending_caps: UnorderedMap<String, spending::SpendingCap>,
+    user_spending: LookupMap<stats::AppUser, spending::Spending>,
+    audit_leaves: Vector<hash::Sha256>,
+    audit_nodes: LookupMap<(u8, u64), hash::Sha256>,
+}
+
+/// The [`Executor`] state as it was first deployed, before the
+/// statistics, relayer keys, spending caps and audit log.
+#[derive(BorshDeserialize)]
+struct ExecutorV0 {
+    owner_ids: UnorderedSet<AccountId>,
 }
 
 #[derive(BorshSerialize, BorshStorageKey)]
 enum StorageKey {
     Owners,
+    AppStats,
+    UserStats,
+    RelayerKeys,
+    SpendingCaps,
+    UserSpending,
+    AuditLeaves,
+    AuditNodes,
 }
 
 #[near_bindgen]
@@ -27,11 +56,38 @@ impl Executor {
         ensure(!env::state_exists(), Error::AlreadyInitialized);
         let mut owner_ids = UnorderedSet::new(StorageKey::Owners);
         owner_ids.insert(&owner_id);
-        Self { owner_ids }
+        Self::with_owners(owner_ids)
+    }
+
+    /// Migrates the state of an executor that was deployed before
+    /// the statistics, relayer keys, spending caps and audit log,
+    /// keeping its owners.
+    ///
+    /// Must be called by the executor itself, eg. in the same batch
+    /// that deploys the new code.
+    #[private]
+    #[init(ignore_state)]
+    pub fn migrate() -> Self {
+        let old: ExecutorV0 = env::state_read().or_panic_str(Error::NotInitialized);
+        Self::with_owners(old.owner_ids)
     }
 }
 
 impl Executor {
+    fn with_owners(owner_ids: UnorderedSet<AccountId>) -> Self {
+        Self {
+            owner_ids,
+            app_stats: UnorderedMap::new(StorageKey::AppStats),
+            user_stats: UnorderedMap::new(StorageKey::UserStats),
+            track_user_stats: false,
+            relayer_keys: UnorderedMap::new(StorageKey::RelayerKeys),
+            spending_caps: UnorderedMap::new(StorageKey::SpendingCaps),
+            user_spending: LookupMap::new(StorageKey::UserSpending),
+            audit_leaves: Vector::new(StorageKey::AuditLeaves),
+            audit_nodes: LookupMap::new(StorageKey::AuditNodes),
+        }
+    }
+
     fn assert_owner(&self) {
         ensure(
             self.owner_ids.contains(&env::predecessor_account_id()),
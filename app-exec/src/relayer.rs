@@ -0,0 +1,93 @@
+use crate::error::{ensure, Error, OrPanicStr};
+use crate::{Executor, MAX_PAGE_LIMIT};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::{U128, U64};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{env, near_bindgen, Promise, PublicKey};
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::ExecutorContract;
+
+/// The only method that relayer keys are allowed to call.
+pub const RELAYER_METHOD: &str = "execute";
+
+/// Information on a function-call access key that was added to the
+/// executor's account for a relayer.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RelayerKey {
+    /// Free-form label, eg. identifying the backend instance that
+    /// holds the key.
+    ///
+    /// The key's remaining allowance is kept by the protocol, and can
+    /// be queried with the `view_access_key` RPC.
+    pub label: String,
+}
+
+#[near_bindgen]
+impl Executor {
+    /// Adds `public_key` as a function-call access key on the
+    /// executor's account, only allowed to call [`Self::execute()`]
+    /// and to spend at most `allowance` in gas fees.
+    ///
+    /// The `allowance` can't be `0`, which Near would take as an
+    /// unlimited allowance.
+    ///
+    /// Calls made with that key have the executor itself as the
+    /// predecessor, which is accepted as an owner only for the
+    /// relayer keys.
+    pub fn add_relayer_key(
+        &mut self,
+        public_key: PublicKey,
+        allowance: U128,
+        label: String,
+    ) -> Promise {
+        self.assert_owner();
+        ensure(allowance.0 != 0, Error::ZeroAllowance);
+
+        let key = RelayerKey { label };
+        let is_new = self.relayer_keys.insert(&public_key, &key).is_none();
+        ensure(is_new, Error::RelayerKeyAlreadyAdded);
+
+        Promise::new(env::current_account_id()).add_access_key(
+            public_key,
+            allowance.0,
+            env::current_account_id(),
+            RELAYER_METHOD.to_string(),
+        )
+    }
+
+    /// Deletes a key that was added by [`Self::add_relayer_key()`]
+    /// from the executor's account.
+    pub fn remove_relayer_key(&mut self, public_key: PublicKey) -> Promise {
+        self.assert_owner();
+
+        self.relayer_keys
+            .remove(&public_key)
+            .or_panic_str(Error::MissingRelayerKey);
+
+        Promise::new(env::current_account_id()).delete_key(public_key)
+    }
+
+    /// Gets the information of a relayer key.
+    pub fn get_relayer_key(&self, public_key: PublicKey) -> Option<RelayerKey> {
+        self.relayer_keys.get(&public_key)
+    }
+
+    /// Lists the relayer keys, starting at the `from_index` position
+    /// (defaults to `0`) and returning at most `limit` entries
+    /// (defaults to and capped at [`MAX_PAGE_LIMIT`]).
+    pub fn get_relayer_keys(
+        &self,
+        from_index: Option<U64>,
+        limit: Option<u64>,
+    ) -> Vec<(PublicKey, RelayerKey)> {
+        let from_index = from_index.map(|i| i.0).unwrap_or_default();
+        let limit = limit.unwrap_or(MAX_PAGE_LIMIT).min(MAX_PAGE_LIMIT);
+        self.relayer_keys
+            .iter()
+            .skip(from_index as usize)
+            .take(limit as usize)
+            .collect()
+    }
+}
@@ -0,0 +1,170 @@
+use crate::error::Error;
+use crate::exec::TagInfo;
+use crate::stats::AppUser;
+use crate::Executor;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{env, near_bindgen, AccountId, Balance};
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::ExecutorContract;
+
+pub const NANOS_PER_DAY: u64 = 24 * 60 * 60 * 1_000_000_000;
+
+/// Limits on how much deposit [`Executor::execute()`] may forward on
+/// behalf of each user of an app.
+///
+/// A missing limit means that there is no such limit.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SpendingCap {
+    /// Limit for all of the user's executions, in yoctoNear.
+    pub lifetime: Option<U128>,
+    /// Limit for the user's executions within the same UTC day, in
+    /// yoctoNear.
+    pub daily: Option<U128>,
+}
+
+/// How much deposit a user has had forwarded.
+#[derive(BorshDeserialize, BorshSerialize, Default)]
+pub struct Spending {
+    pub lifetime: Balance,
+    /// The day, counted since the unix epoch, that
+    /// [`Self::daily`] refers to.
+    pub day: u64,
+    pub daily: Balance,
+}
+
+impl Spending {
+    /// Spending of the current day.
+    fn daily(&self, today: u64) -> Balance {
+        if self.day == today {
+            self.daily
+        } else {
+            0
+        }
+    }
+}
+
+/// How much deposit can still be forwarded on behalf of a user.
+///
+/// A missing value means that there is no such limit.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RemainingAllowance {
+    pub lifetime: Option<U128>,
+    pub daily: Option<U128>,
+}
+
+#[near_bindgen]
+impl Executor {
+    /// Sets the per-user spending cap of an `app_id`.
+    ///
+    /// A missing `cap` removes the limits.  
+    /// The users' spending is only tracked while the app has a cap.
+    pub fn set_spending_cap(&mut self, app_id: String, cap: Option<SpendingCap>) {
+        self.assert_owner();
+        match cap {
+            Some(cap) => self.spending_caps.insert(&app_id, &cap),
+            None => self.spending_caps.remove(&app_id),
+        };
+    }
+
+    /// Gets the per-user spending cap of an `app_id`.
+    pub fn get_spending_cap(&self, app_id: String) -> Option<SpendingCap> {
+        self.spending_caps.get(&app_id)
+    }
+
+    /// Gets how much deposit can still be forwarded on behalf of
+    /// `user_id` on `app_id`.
+    pub fn get_remaining_allowance(
+        &self,
+        app_id: String,
+        user_id: AccountId,
+    ) -> RemainingAllowance {
+        let cap = self.spending_caps.get(&app_id);
+        let cap = match cap {
+            Some(cap) => cap,
+            None => {
+                return RemainingAllowance {
+                    lifetime: None,
+                    daily: None,
+                }
+            }
+        };
+        let spending = self
+            .user_spending
+            .get(&(app_id, user_id))
+            .unwrap_or_default();
+        let today = env::block_timestamp() / NANOS_PER_DAY;
+        RemainingAllowance {
+            lifetime: cap
+                .lifetime
+                .map(|limit| limit.0.saturating_sub(spending.lifetime).into()),
+            daily: cap
+                .daily
+                .map(|limit| limit.0.saturating_sub(spending.daily(today)).into()),
+        }
+    }
+}
+
+impl Executor {
+    /// Collects the spending cap violations of forwarding `deposit` on
+    /// behalf of the user.
+    pub(crate) fn check_spending(&self, tag_info: &TagInfo, deposit: Balance) -> Vec<Error> {
+        let mut violations = vec![];
+        let remaining =
+            self.get_remaining_allowance(tag_info.app_id.clone(), tag_info.user_id.clone());
+
+        if matches!(remaining.lifetime, Some(limit) if deposit > limit.0) {
+            violations.push(Error::LifetimeSpendingCapExceeded);
+        }
+        if matches!(remaining.daily, Some(limit) if deposit > limit.0) {
+            violations.push(Error::DailySpendingCapExceeded);
+        }
+
+        violations
+    }
+
+    /// Adds `deposit` to the user's spending, if the app has a
+    /// spending cap.
+    pub(crate) fn record_spending(&mut self, tag_info: &TagInfo, deposit: Balance) {
+        if self.spending_caps.get(&tag_info.app_id).is_none() {
+            return;
+        }
+
+        let key: AppUser = (tag_info.app_id.clone(), tag_info.user_id.clone());
+        let today = env::block_timestamp() / NANOS_PER_DAY;
+        let spending = self.user_spending.get(&key).unwrap_or_default();
+        let spending = Spending {
+            lifetime: spending.lifetime + deposit,
+            day: today,
+            daily: spending.daily(today) + deposit,
+        };
+        self.user_spending.insert(&key, &spending);
+    }
+
+    /// Removes a `deposit` that was recorded at `timestamp` from the
+    /// user's spending, as the call that it was forwarded to failed
+    /// and so it was refunded.
+    ///
+    /// The daily spending is only reduced if it's still for the day of
+    /// the `timestamp`.
+    pub(crate) fn revert_spending(&mut self, tag_info: &TagInfo, deposit: Balance, timestamp: u64) {
+        if self.spending_caps.get(&tag_info.app_id).is_none() {
+            return;
+        }
+
+        let key: AppUser = (tag_info.app_id.clone(), tag_info.user_id.clone());
+        let mut spending = match self.user_spending.get(&key) {
+            Some(spending) => spending,
+            None => return,
+        };
+        spending.lifetime = spending.lifetime.saturating_sub(deposit);
+        if spending.day == timestamp / NANOS_PER_DAY {
+            spending.daily = spending.daily.saturating_sub(deposit);
+        }
+        self.user_spending.insert(&key, &spending);
+    }
+}
@@ -0,0 +1,187 @@
+use crate::exec::TagInfo;
+use crate::{Executor, MAX_PAGE_LIMIT};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::{U128, U64};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{env, near_bindgen, AccountId, Balance};
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::ExecutorContract;
+
+/// Aggregated counters of the executions made through
+/// [`Executor::execute()`].
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ExecutionStats {
+    /// How many executions were started.
+    pub executions: U64,
+    /// How many executions had their contract call succeed.
+    pub successes: U64,
+    /// How many executions had their contract call fail.
+    ///
+    /// Executions that are still waiting for their contract call
+    /// are neither counted as successes nor as failures.
+    pub failures: U64,
+    /// The total deposit forwarded into the contract calls, in
+    /// yoctoNear.
+    pub deposit: U128,
+    /// The block timestamp, in nanoseconds, of the last execution
+    /// start or result.
+    pub last_activity: U64,
+}
+
+impl Default for ExecutionStats {
+    fn default() -> Self {
+        Self {
+            executions: U64(0),
+            successes: U64(0),
+            failures: U64(0),
+            deposit: U128(0),
+            last_activity: U64(0),
+        }
+    }
+}
+
+impl ExecutionStats {
+    fn on_start(&mut self, deposit: Balance) {
+        self.executions.0 += 1;
+        self.deposit.0 += deposit;
+        self.last_activity = env::block_timestamp().into();
+    }
+
+    fn on_result(&mut self, success: bool) {
+        if success {
+            self.successes.0 += 1;
+        } else {
+            self.failures.0 += 1;
+        }
+        self.last_activity = env::block_timestamp().into();
+    }
+}
+
+/// Key of the per-user statistics.
+pub type AppUser = (String, AccountId);
+
+#[near_bindgen]
+impl Executor {
+    /// Gets the statistics of an `app_id`.
+    pub fn get_app_stats(&self, app_id: String) -> Option<ExecutionStats> {
+        self.app_stats.get(&app_id)
+    }
+
+    /// Lists the statistics of every app, starting at the
+    /// `from_index` position (defaults to `0`) and returning at most
+    /// `limit` entries (defaults to and capped at [`MAX_PAGE_LIMIT`]).
+    pub fn get_apps_stats(
+        &self,
+        from_index: Option<U64>,
+        limit: Option<u64>,
+    ) -> Vec<(String, ExecutionStats)> {
+        let from_index = from_index.map(|i| i.0).unwrap_or_default();
+        let limit = limit.unwrap_or(MAX_PAGE_LIMIT).min(MAX_PAGE_LIMIT);
+        self.app_stats
+            .iter()
+            .skip(from_index as usize)
+            .take(limit as usize)
+            .collect()
+    }
+
+    /// Gets the statistics of a `user_id` on an `app_id`.
+    ///
+    /// Those are only gathered while
+    /// [`Self::set_user_stats_tracking()`] is enabled.
+    pub fn get_user_stats(&self, app_id: String, user_id: AccountId) -> Option<ExecutionStats> {
+        self.user_stats.get(&(app_id, user_id))
+    }
+
+    /// Lists the statistics of every user on every app, starting at
+    /// the `from_index` position (defaults to `0`) and returning at
+    /// most `limit` entries (defaults to and capped at
+    /// [`MAX_PAGE_LIMIT`]).
+    pub fn get_users_stats(
+        &self,
+        from_index: Option<U64>,
+        limit: Option<u64>,
+    ) -> Vec<(AppUser, ExecutionStats)> {
+        let from_index = from_index.map(|i| i.0).unwrap_or_default();
+        let limit = limit.unwrap_or(MAX_PAGE_LIMIT).min(MAX_PAGE_LIMIT);
+        self.user_stats
+            .iter()
+            .skip(from_index as usize)
+            .take(limit as usize)
+            .collect()
+    }
+
+    /// Whether statistics are also gathered per user.
+    pub fn is_user_stats_tracking(&self) -> bool {
+        self.track_user_stats
+    }
+
+    /// Enables or disables gathering statistics per user.
+    ///
+    /// Disabling doesn't remove the already gathered statistics.
+    pub fn set_user_stats_tracking(&mut self, enabled: bool) {
+        self.assert_owner();
+        self.track_user_stats = enabled;
+    }
+
+    /// Removes the statistics of an `app_id`.
+    ///
+    /// Returns `true` if there were any.
+    pub fn reset_app_stats(&mut self, app_id: String) -> bool {
+        self.assert_owner();
+        self.app_stats.remove(&app_id).is_some()
+    }
+
+    /// Removes the statistics of a `user_id` on an `app_id`.
+    ///
+    /// Returns `true` if there were any.
+    pub fn reset_user_stats(&mut self, app_id: String, user_id: AccountId) -> bool {
+        self.assert_owner();
+        self.user_stats.remove(&(app_id, user_id)).is_some()
+    }
+
+    /// Removes all of the app and user statistics.
+    pub fn reset_stats(&mut self) {
+        self.assert_owner();
+        self.app_stats.clear();
+        self.user_stats.clear();
+    }
+}
+
+impl Executor {
+    /// Counts a new execution for the app and, if tracked, for the
+    /// user.
+    pub(crate) fn record_execution_start(&mut self, tag_info: &TagInfo, deposit: Balance) {
+        let mut stats = self.app_stats.get(&tag_info.app_id).unwrap_or_default();
+        stats.on_start(deposit);
+        self.app_stats.insert(&tag_info.app_id, &stats);
+
+        if self.track_user_stats {
+            let key = (tag_info.app_id.clone(), tag_info.user_id.clone());
+            let mut stats = self.user_stats.get(&key).unwrap_or_default();
+            stats.on_start(deposit);
+            self.user_stats.insert(&key, &stats);
+        }
+    }
+
+    /// Counts the result of an execution for the app and, if
+    /// `track_user`, for the user.
+    pub(crate) fn record_execution_result(
+        &mut self,
+        tag_info: &TagInfo,
+        track_user: bool,
+        success: bool,
+    ) {
+        let mut stats = self.app_stats.get(&tag_info.app_id).unwrap_or_default();
+        stats.on_result(success);
+        self.app_stats.insert(&tag_info.app_id, &stats);
+
+        if track_user {
+            let key = (tag_info.app_id.clone(), tag_info.user_id.clone());
+            let mut stats = self.user_stats.get(&key).unwrap_or_default();
+            stats.on_result(success);
+            self.user_stats.insert(&key, &stats);
+        }
+    }
+}
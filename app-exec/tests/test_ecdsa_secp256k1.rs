@@ -1,7 +1,8 @@
 #![allow(clippy::ref_in_deref)]
 
-use crate::utils::{_secp256k1, setup_exec, TERA};
+use crate::utils::{_secp256k1, hex, keys, setup_exec, sign, AssertFailure, TERA};
 use near_sdk_sim::{call, init_simulator};
+use nearapps_exec::error::Error;
 
 mod utils;
 
@@ -19,12 +20,8 @@ fn test_ecdsa_secp256k1() {
 
     let seckey = ec::types::SecKey(seckey);
 
-    // generates the pubkey on the contract
-    let pubkey_compressed: ec::types::PubKeyCompressed = {
-        let res = call!(&root, contract.secp256k1_pubkey_compressed(seckey.clone()));
-        assert!(res.gas_burnt().0 < 33 * TERA);
-        res.unwrap_json()
-    };
+    // generates the pubkey off-chain
+    let pubkey_compressed = keys::secp256k1_pubkey_compressed(seckey.clone());
     let pubkey_compressed = &pubkey_compressed;
     let expected_pubkey = [
         2, 29, 21, 35, 7, 198, 183, 43, 14, 208, 65, 139, 14, 112, 205, 128, 231, 245, 41, 91, 141,
@@ -32,27 +29,13 @@ fn test_ecdsa_secp256k1() {
     ];
     assert_eq!(pubkey_compressed.0, expected_pubkey);
 
-    let pubkey_uncompressed: ec::types::PubKeyUncompressed = {
-        let res = call!(
-            &root,
-            contract.secp256k1_pubkey_uncompressed(seckey.clone())
-        );
-        assert!(res.gas_burnt().0 < 33 * TERA);
-        res.unwrap_json()
-    };
+    let pubkey_uncompressed = keys::secp256k1_pubkey_uncompressed(seckey.clone());
     let pubkey_uncompressed = &pubkey_uncompressed;
 
     let msg = "This is some message";
 
-    // sign the message on the contract
-    let sign: ec::types::SignCompact = {
-        let res = call!(
-            &root,
-            contract.ecdsa_secp256k1_sign(seckey.clone(), msg.to_string())
-        );
-        assert!(res.gas_burnt().0 < 45 * TERA);
-        res.unwrap_json()
-    };
+    // sign the message off-chain
+    let sign = sign::ecdsa_secp256k1_sign(seckey.clone(), msg.as_bytes());
     let sign = &sign;
 
     // copy and change the resulting signature
@@ -72,7 +55,8 @@ fn test_ecdsa_secp256k1() {
             contract.ecdsa_secp256k1_verify_compressed_msg(
                 pubkey_compressed.clone(),
                 sign.clone(),
-                msg.to_string()
+                msg.to_string(),
+                None
             )
         );
         assert!(res.gas_burnt().0 < 65 * TERA);
@@ -87,7 +71,8 @@ fn test_ecdsa_secp256k1() {
             contract.ecdsa_secp256k1_verify_compressed_msg(
                 pubkey_compressed.clone(),
                 bad_sign.clone(),
-                msg.to_string()
+                msg.to_string(),
+                None
             )
         );
         assert!(res.gas_burnt().0 < 65 * TERA);
@@ -108,7 +93,8 @@ fn test_ecdsa_secp256k1() {
             contract.ecdsa_secp256k1_verify_prehashed_compressed(
                 pubkey_compressed.clone(),
                 sign.clone(),
-                msg_hash
+                msg_hash,
+                None
             )
         );
         assert!(res.gas_burnt().0 < 65 * TERA);
@@ -129,7 +115,8 @@ fn test_ecdsa_secp256k1() {
             contract.ecdsa_secp256k1_verify_prehashed_compressed(
                 pubkey_compressed.clone(),
                 bad_sign.clone(),
-                msg_hash
+                msg_hash,
+                None
             )
         );
         assert!(res.gas_burnt().0 < 65 * TERA);
@@ -144,7 +131,8 @@ fn test_ecdsa_secp256k1() {
             contract.ecdsa_secp256k1_verify_uncompressed_msg(
                 pubkey_uncompressed.clone(),
                 sign.clone(),
-                msg.to_string()
+                msg.to_string(),
+                None
             )
         );
         assert!(res.gas_burnt().0 < 65 * TERA);
@@ -159,7 +147,8 @@ fn test_ecdsa_secp256k1() {
             contract.ecdsa_secp256k1_verify_uncompressed_msg(
                 pubkey_uncompressed.clone(),
                 bad_sign.clone(),
-                msg.to_string()
+                msg.to_string(),
+                None
             )
         );
         assert!(res.gas_burnt().0 < 65 * TERA);
@@ -181,7 +170,8 @@ fn test_ecdsa_secp256k1() {
             contract.ecdsa_secp256k1_verify_prehashed_uncompressed(
                 pubkey_uncompressed.clone(),
                 sign.clone(),
-                msg_hash
+                msg_hash,
+                None
             )
         );
         assert!(res.gas_burnt().0 < 65 * TERA);
@@ -202,7 +192,8 @@ fn test_ecdsa_secp256k1() {
             contract.ecdsa_secp256k1_verify_prehashed_uncompressed(
                 pubkey_uncompressed.clone(),
                 bad_sign.clone(),
-                msg_hash
+                msg_hash,
+                None
             )
         );
         assert!(res.gas_burnt().0 < 65 * TERA);
@@ -239,3 +230,187 @@ fn test_ecdsa_secp256k1() {
         assert_eq!(sign, &sign2);
     }
 }
+
+#[test]
+fn test_ecdsa_secp256k1_strict() {
+    use nearapps_exec::crypto::ecdsa_secp256k1::types::{SecKey, SignCompact, SignDer, VerifyMode};
+
+    let root = init_simulator(None);
+    let contract = setup_exec(&root);
+
+    let seckey = SecKey([2; 32]);
+    let pubkey = keys::secp256k1_pubkey_compressed(seckey.clone());
+    let msg = "This is some message";
+    let sign = sign::ecdsa_secp256k1_sign(seckey, msg.as_bytes());
+
+    // the twin signature, with `n - s`
+    let high_s = {
+        let s = k256::Scalar::from_bytes_reduced(k256::FieldBytes::from_slice(&sign.0[32..]));
+        let mut high_s = sign.clone();
+        high_s.0[32..].copy_from_slice(&(-s).to_bytes());
+        high_s
+    };
+
+    let verify = |sign: &SignCompact, mode: Option<VerifyMode>| {
+        let res: bool = call!(
+            &root,
+            contract.ecdsa_secp256k1_verify_compressed_msg(
+                pubkey.clone(),
+                sign.clone(),
+                msg.to_string(),
+                mode
+            )
+        )
+        .unwrap_json();
+        res
+    };
+    let verify_der = |sign: &SignDer, mode: Option<VerifyMode>| {
+        call!(
+            &root,
+            contract.ecdsa_secp256k1_verify_der_compressed_msg(
+                pubkey.clone(),
+                sign.clone(),
+                msg.to_string(),
+                mode
+            )
+        )
+    };
+
+    // ok: the lower-S form in both modes
+    assert!(verify(&sign, None));
+    assert!(verify(&sign, Some(VerifyMode::Strict)));
+    assert!(verify(&sign, Some(VerifyMode::Lenient)));
+
+    // ok: the higher-S form is accepted by default, as it's lenient
+    assert!(verify(&high_s, None));
+    assert!(verify(&high_s, Some(VerifyMode::Lenient)));
+
+    // fail: unless it's strict
+    assert!(!verify(&high_s, Some(VerifyMode::Strict)));
+
+    // fail: a zero `s`, in both modes
+    let mut zero_s = sign.clone();
+    zero_s.0[32..].copy_from_slice(&[0; 32]);
+    assert!(!verify(&zero_s, None));
+    assert!(!verify(&zero_s, Some(VerifyMode::Lenient)));
+
+    // fail: a pubkey with an invalid SEC1 header
+    let mut bad_pubkey = pubkey.clone();
+    bad_pubkey.0[0] = 0x05;
+    let res: bool = call!(
+        &root,
+        contract.ecdsa_secp256k1_verify_compressed_msg(
+            bad_pubkey,
+            sign.clone(),
+            msg.to_string(),
+            Some(VerifyMode::Lenient)
+        )
+    )
+    .unwrap_json();
+    assert!(!res);
+
+    // ok: DER encoded, with the same modes
+    let der: SignDer = sign.into();
+    assert!(verify_der(&der, None).unwrap_json::<bool>());
+    let high_s_der: SignDer = high_s.into();
+    assert!(verify_der(&high_s_der, None).unwrap_json::<bool>());
+    assert!(!verify_der(&high_s_der, Some(VerifyMode::Strict)).unwrap_json::<bool>());
+
+    // fail: non-canonical DER, where `r` has an extra zero
+    let padded = {
+        let r_len = der.0[3] as usize;
+        let mut padded = vec![0x30, der.0[1] + 1, 0x02, der.0[3] + 1, 0];
+        padded.extend(&der.0[4..]);
+        assert_eq!(padded.len(), der.0.len() + 1);
+        assert_eq!(&padded[5..5 + r_len], &der.0[4..4 + r_len]);
+        SignDer(padded)
+    };
+    let res = verify_der(&padded, Some(VerifyMode::Lenient));
+    res.assert_failure(0, Error::BadDerSignature);
+
+    // fail: trailing bytes
+    let mut trailing = der;
+    trailing.0.push(0);
+    let res = verify_der(&trailing, None);
+    res.assert_failure(0, Error::BadDerSignature);
+}
+
+#[test]
+fn test_ecdsa_secp256k1_recover() {
+    use nearapps_exec::{crypto::ecdsa_secp256k1 as ec, hash};
+    use std::convert::TryFrom;
+
+    let root = init_simulator(None);
+    let contract = setup_exec(&root);
+
+    let seckey = ec::types::SecKey([
+        59, 148, 11, 85, 134, 130, 61, 253, 2, 174, 59, 70, 27, 180, 51, 107, 94, 203, 174, 253,
+        102, 39, 170, 146, 46, 252, 4, 143, 236, 12, 136, 28,
+    ]);
+    let msg = "This is some message";
+
+    let pubkey_compressed = keys::secp256k1_pubkey_compressed(seckey.clone());
+    let pubkey_uncompressed = keys::secp256k1_pubkey_uncompressed(seckey.clone());
+    let sign = sign::ecdsa_secp256k1_sign_recoverable(seckey, msg.as_bytes());
+
+    // ok: recovers from the msg
+    let recovered: ec::types::RecoveredPubKey = {
+        let res = call!(
+            &root,
+            contract.ecdsa_secp256k1_recover(sign.clone(), msg.to_string())
+        );
+        assert!(res.gas_burnt().0 < 65 * TERA);
+        res.unwrap_json()
+    };
+    assert_eq!(recovered.compressed, pubkey_compressed);
+    assert_eq!(recovered.uncompressed, pubkey_uncompressed);
+    let near_pubkey: near_sdk::PublicKey = recovered.near.0.parse().unwrap();
+    assert_eq!(near_pubkey.as_bytes()[1..], pubkey_uncompressed.0[1..]);
+
+    // ok: recovers from the msg hash
+    let msg_hash: hash::Sha256 =
+        call!(&root, contract.hash_sha256(msg.as_bytes().to_vec())).unwrap_json();
+    let recovered2: ec::types::RecoveredPubKey = call!(
+        &root,
+        contract.ecdsa_secp256k1_recover_prehashed(sign.clone(), msg_hash)
+    )
+    .unwrap_json();
+    assert_eq!(recovered, recovered2);
+
+    // fail: a different msg recovers into a different pubkey
+    let recovered3: ec::types::RecoveredPubKey = call!(
+        &root,
+        contract.ecdsa_secp256k1_recover(sign.clone(), msg.to_string() + "0")
+    )
+    .unwrap_json();
+    assert!(recovered3.compressed != pubkey_compressed);
+
+    // fail: an invalid recovery id
+    let mut bad_sign = sign;
+    bad_sign.0[64] = 4;
+    assert_eq!(
+        ec::types::SignCompact::try_from(bad_sign.clone()),
+        Err(Error::NonCanonicalSignature)
+    );
+    let res = call!(
+        &root,
+        contract.ecdsa_secp256k1_recover(bad_sign, msg.to_string())
+    );
+    res.assert_failure(0, "ERR_EXEC_RECOVER_FAILED");
+
+    // fail: recovers to the identity, as `R` is the generator and `s`
+    // is the msg hash, so `r⁻¹ (s R - z G)` is zero
+    let generator = hex("0479be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8");
+    // the `y` of the generator is even
+    assert_eq!(generator[64] % 2, 0);
+    let msg_hash = hash::Sha256([1; 32]);
+    let mut identity_sign = [0; 65];
+    identity_sign[..32].copy_from_slice(&generator[1..33]);
+    identity_sign[32..64].copy_from_slice(&msg_hash.0);
+    let res = call!(
+        &root,
+        contract
+            .ecdsa_secp256k1_recover_prehashed(ec::types::SignRecoverable(identity_sign), msg_hash)
+    );
+    res.assert_failure(0, Error::RecoverFailed);
+}
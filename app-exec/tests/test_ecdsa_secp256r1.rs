@@ -0,0 +1,130 @@
+#![allow(clippy::ref_in_deref)]
+
+use crate::utils::{hex, keys, setup_exec, sign};
+use near_sdk_sim::{call, init_simulator};
+use nearapps_exec::crypto::ecdsa_secp256r1 as r1;
+use std::convert::TryInto;
+
+mod utils;
+
+#[test]
+fn test_ecdsa_secp256r1() {
+    let root = init_simulator(None);
+    let contract = setup_exec(&root);
+
+    // ok: the P-256 and SHA-256 "sample" vector from rfc6979 (A.2.5),
+    // which has a higher-S signature
+    let pubkey = r1::types::PubKeyUncompressed(
+        hex("0460fed4ba255a9d31c961eb74c6356d68c049b8923b61fa6ce669622e60f29fb67903fe1008b8bc99a41ae9e95628bc64f2f1b20c2d7e9f5177a3c294d4462299")
+            .try_into()
+            .unwrap(),
+    );
+    let rfc_sign = r1::types::SignCompact(
+        hex("efd48b2aacb6a8fd1140dd9cd45e81d69d2c877b56aaf991c34d0ea84eaf3716f7cb1c942d657c41d436c7a1b6e29f65f3e900dbb9aff4064dc4ab2f843acda8")
+            .try_into()
+            .unwrap(),
+    );
+    let verify1: bool = call!(
+        &root,
+        contract.ecdsa_secp256r1_verify_uncompressed_msg(
+            pubkey.clone(),
+            rfc_sign.clone(),
+            "sample".to_string()
+        )
+    )
+    .unwrap_json();
+    assert!(verify1);
+
+    // fail: a different msg
+    let verify2: bool = call!(
+        &root,
+        contract.ecdsa_secp256r1_verify_uncompressed_msg(pubkey, rfc_sign, "test".to_string())
+    )
+    .unwrap_json();
+    assert!(!verify2);
+
+    // signs off-chain
+    let seckey = r1::types::SecKey([
+        0xc9, 0xaf, 0xa9, 0xd8, 0x45, 0xba, 0x75, 0x16, 0x6b, 0x5c, 0x21, 0x57, 0x67, 0xb1, 0xd6,
+        0x93, 0x4e, 0x50, 0xc3, 0xdb, 0x36, 0xe8, 0x9b, 0x12, 0x7b, 0x8a, 0x62, 0x2b, 0x12, 0x0f,
+        0x67, 0x21,
+    ]);
+    let pubkey = keys::secp256r1_pubkey_uncompressed(seckey.clone());
+    let pubkey_compressed = {
+        let pubkey = p256::PublicKey::from_sec1_bytes(&pubkey.0).unwrap();
+        r1::types::PubKeyCompressed::from(pubkey)
+    };
+    let msg = "This is some message";
+    let sign = sign::ecdsa_secp256r1_sign(seckey, msg.as_bytes());
+
+    // ok: compressed pubkey
+    let verify3: bool = call!(
+        &root,
+        contract.ecdsa_secp256r1_verify_compressed_msg(
+            pubkey_compressed.clone(),
+            sign.clone(),
+            msg.to_string()
+        )
+    )
+    .unwrap_json();
+    assert!(verify3);
+
+    // ok: prehashed
+    let verify4: bool = call!(
+        &root,
+        contract.ecdsa_secp256r1_verify_prehashed_uncompressed(
+            pubkey.clone(),
+            sign.clone(),
+            sign::sha256(msg.as_bytes())
+        )
+    )
+    .unwrap_json();
+    assert!(verify4);
+
+    // fail: a changed signature
+    let bad_sign = {
+        let mut bad_sign = sign.clone();
+        bad_sign.0[63] = bad_sign.0[63].wrapping_add(1);
+        bad_sign
+    };
+    let verify5: bool = call!(
+        &root,
+        contract.ecdsa_secp256r1_verify_prehashed_compressed(
+            pubkey_compressed,
+            bad_sign,
+            sign::sha256(msg.as_bytes())
+        )
+    )
+    .unwrap_json();
+    assert!(!verify5);
+
+    // fail: a pubkey that is not on the curve
+    let mut off_curve = [0; 65];
+    off_curve[0] = 0x04;
+    off_curve[32] = 1;
+    off_curve[64] = 1;
+    let verify6: bool = call!(
+        &root,
+        contract.ecdsa_secp256r1_verify_prehashed_uncompressed(
+            r1::types::PubKeyUncompressed(off_curve),
+            sign.clone(),
+            sign::sha256(msg.as_bytes())
+        )
+    )
+    .unwrap_json();
+    assert!(!verify6);
+
+    // fail: a zero `s`
+    let mut zero_s = sign;
+    zero_s.0[32..].copy_from_slice(&[0; 32]);
+    let verify7: bool = call!(
+        &root,
+        contract.ecdsa_secp256r1_verify_prehashed_uncompressed(
+            pubkey,
+            zero_s,
+            sign::sha256(msg.as_bytes())
+        )
+    )
+    .unwrap_json();
+    assert!(!verify7);
+}
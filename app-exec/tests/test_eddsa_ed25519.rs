@@ -1,10 +1,76 @@
 #![allow(clippy::ref_in_deref)]
 
-use crate::utils::{setup_exec, TERA};
+use crate::utils::{hex, keys, setup_exec, sign, TERA};
 use near_sdk_sim::{call, init_simulator};
 
 mod utils;
 
+/// The edge cases of
+/// ["Taming the many EdDSAs"](https://eprint.iacr.org/2020/1244), as
+/// `(msg, pubkey, sign)`.
+const TAMING_EDDSA_VECTORS: &[(&str, &str, &str)] = &[
+    (
+        "8c93255d71dcab10e8f379c26200f3c7bd5f09d9bc3068d3ef4edeb4853022b6",
+        "c7176a703d4dd84fba3c0b760d10670f2a2053fa2c39ccc64ec7fd7792ac03fa",
+        "c7176a703d4dd84fba3c0b760d10670f2a2053fa2c39ccc64ec7fd7792ac037a0000000000000000000000000000000000000000000000000000000000000000",
+    ),
+    (
+        "9bd9f44f4dcc75bd531b56b2cd280b0bb38fc1cd6d1230e14861d861de092e79",
+        "c7176a703d4dd84fba3c0b760d10670f2a2053fa2c39ccc64ec7fd7792ac03fa",
+        "f7badec5b8abeaf699583992219b7b223f1df3fbbea919844e3f7c554a43dd43a5bb704786be79fc476f91d3f3f89b03984d8068dcf1bb7dfc6637b45450ac04",
+    ),
+    (
+        "aebf3f2601a0c8c5d39cc7d8911642f740b78168218da8471772b35f9d35b9ab",
+        "f7badec5b8abeaf699583992219b7b223f1df3fbbea919844e3f7c554a43dd43",
+        "c7176a703d4dd84fba3c0b760d10670f2a2053fa2c39ccc64ec7fd7792ac03fa8c4bd45aecaca5b24fb97bc10ac27ac8751a7dfe1baff8b953ec9f5833ca260e",
+    ),
+    (
+        "9bd9f44f4dcc75bd531b56b2cd280b0bb38fc1cd6d1230e14861d861de092e79",
+        "cdb267ce40c5cd45306fa5d2f29731459387dbf9eb933b7bd5aed9a765b88d4d",
+        "9046a64750444938de19f227bb80485e92b83fdb4b6506c160484c016cc1852f87909e14428a7a1d62e9f22f3d3ad7802db02eb2e688b6c52fcd6648a98bd009",
+    ),
+    (
+        "e47d62c63f830dc7a6851a0b1f33ae4bb2f507fb6cffec4011eaccd55b53f56c",
+        "cdb267ce40c5cd45306fa5d2f29731459387dbf9eb933b7bd5aed9a765b88d4d",
+        "160a1cb0dc9c0258cd0a7d23e94d8fa878bcb1925f2c64246b2dee1796bed5125ec6bc982a269b723e0668e540911a9a6a58921d6925e434ab10aa7940551a09",
+    ),
+    (
+        "e47d62c63f830dc7a6851a0b1f33ae4bb2f507fb6cffec4011eaccd55b53f56c",
+        "cdb267ce40c5cd45306fa5d2f29731459387dbf9eb933b7bd5aed9a765b88d4d",
+        "21122a84e0b5fca4052f5b1235c80a537878b38f3142356b2c2384ebad4668b7e40bc836dac0f71076f9abe3a53f9c03c1ceeeddb658d0030494ace586687405",
+    ),
+    (
+        "85e241a07d148b41e47d62c63f830dc7a6851a0b1f33ae4bb2f507fb6cffec40",
+        "442aad9f089ad9e14647b1ef9099a1ff4798d78589e66f28eca69c11f582a623",
+        "e96f66be976d82e60150baecff9906684aebb1ef181f67a7189ac78ea23b6c0e547f7690a0e2ddcd04d87dbc3490dc19b3b3052f7ff0538cb68afb369ba3a514",
+    ),
+    (
+        "85e241a07d148b41e47d62c63f830dc7a6851a0b1f33ae4bb2f507fb6cffec40",
+        "442aad9f089ad9e14647b1ef9099a1ff4798d78589e66f28eca69c11f582a623",
+        "8ce5b96c8f26d0ab6c47958c9e68b937104cd36e13c33566acd2fe8d38aa19427e71f98a473474f2f13f06f97c20d58cc3f54b8bd0d272f42b695dd7e89a8c22",
+    ),
+    (
+        "9bedc267423725d473888631ebf45988bad3db83851ee85c85e241a07d148b41",
+        "f7badec5b8abeaf699583992219b7b223f1df3fbbea919844e3f7c554a43dd43",
+        "ecffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f03be9678ac102edcd92b0210bb34d7428d12ffc5df5f37e359941266a4e35f0f",
+    ),
+    (
+        "9bedc267423725d473888631ebf45988bad3db83851ee85c85e241a07d148b41",
+        "f7badec5b8abeaf699583992219b7b223f1df3fbbea919844e3f7c554a43dd43",
+        "ecffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffca8c5b64cd208982aa38d4936621a4775aa233aa0505711d8fdcfdaa943d4908",
+    ),
+    (
+        "e96b7021eb39c1a163b6da4e3093dcd3f21387da4cc4572be588fafae23c155b",
+        "ecffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+        "a9d55260f765261eb9b84e106f665e00b867287a761990d7135963ee0a7d59dca5bb704786be79fc476f91d3f3f89b03984d8068dcf1bb7dfc6637b45450ac04",
+    ),
+    (
+        "39a591f5321bbe07fd5a23dc2f39d025d74526615746727ceefd6e82ae65c06f",
+        "ecffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+        "a9d55260f765261eb9b84e106f665e00b867287a761990d7135963ee0a7d59dca5bb704786be79fc476f91d3f3f89b03984d8068dcf1bb7dfc6637b45450ac04",
+    ),
+];
+
 #[allow(clippy::zero_prefixed_literal)]
 #[test]
 fn test_eddsa_ed25519() {
@@ -35,22 +101,12 @@ fn test_eddsa_ed25519() {
         041, 081, 063, 120, 126, 100, 092, 059, 050, 011,
     ];
 
-    // ok: get the pub key using the contract
-    let pubkey: ed::types::PubKey = {
-        let seckey = ed::types::SecKey(seckey_bytes);
-        let res = call!(&root, contract.ed25519_pubkey(seckey));
-        assert!(res.gas_burnt().0 < 13 * TERA);
-        res.unwrap_json()
-    };
+    // ok: get the pub key off-chain
+    let pubkey = keys::ed25519_pubkey(ed::types::SecKey(seckey_bytes));
     assert_eq!(pubkey.0, expected_pubkey_bytes);
 
-    // ok: sign the msg using the contract
-    let sign: ed::types::Sign = {
-        let seckey = ed::types::SecKey(seckey_bytes);
-        let res = call!(&root, contract.eddsa_ed25519_sign(seckey, msg.to_string()));
-        assert!(res.gas_burnt().0 < 23 * TERA);
-        res.unwrap_json()
-    };
+    // ok: sign the msg off-chain
+    let sign = sign::eddsa_ed25519_sign(ed::types::SecKey(seckey_bytes), msg.as_bytes());
     assert_eq!(sign.0, expected_sign_bytes);
 
     // ok: get the msg hash (to be used by prehashed calls)
@@ -70,16 +126,8 @@ fn test_eddsa_ed25519() {
 
     // ok: sign the prehashed version
     // note: this results in a different signature from the normal sign!
-    let prehashed_sign: ed::types::SignPrehashed = {
-        let seckey = ed::types::SecKey(seckey_bytes);
-
-        let res = call!(
-            &root,
-            contract.eddsa_ed25519_sign_prehashed(seckey, msg_hash.clone(), None)
-        );
-        assert!(res.gas_burnt().0 < 24 * TERA);
-        res.unwrap_json()
-    };
+    let prehashed_sign =
+        sign::eddsa_ed25519_sign_prehashed(ed::types::SecKey(seckey_bytes), msg_hash.clone(), None);
 
     // ok: creates a bad sign, to test failure cases
     let bad_sign = {
@@ -93,7 +141,7 @@ fn test_eddsa_ed25519() {
     let verify1: bool = {
         let res = call!(
             &root,
-            contract.eddsa_ed25519_verify_msg(pubkey.clone(), sign.clone(), msg.to_string())
+            contract.eddsa_ed25519_verify_msg(pubkey.clone(), sign.clone(), msg.to_string(), None)
         );
         assert!(res.gas_burnt().0 < 35 * TERA);
         res.unwrap_json()
@@ -104,7 +152,7 @@ fn test_eddsa_ed25519() {
     let bad_verify1: bool = {
         let res = call!(
             &root,
-            contract.eddsa_ed25519_verify_msg(pubkey.clone(), bad_sign, msg.to_string())
+            contract.eddsa_ed25519_verify_msg(pubkey.clone(), bad_sign, msg.to_string(), None)
         );
         assert!(res.gas_burnt().0 < 35 * TERA);
         res.unwrap_json()
@@ -182,7 +230,8 @@ fn test_near_verification() {
             contract.verify_msg(
                 sign.to_string().into(),
                 pubkey.to_string().into(),
-                msg.to_string()
+                msg.to_string(),
+                None
             )
         );
         res.assert_success();
@@ -195,7 +244,8 @@ fn test_near_verification() {
             contract.verify_msg(
                 sign.to_string().into(),
                 pubkey.to_string().into(),
-                msg.to_string() + "0"
+                msg.to_string() + "0",
+                None
             )
         )
         .unwrap_json();
@@ -205,7 +255,12 @@ fn test_near_verification() {
         let sign3 = "3".to_string() + &sign.chars().skip(1).collect::<String>();
         let verify3: bool = call!(
             &root,
-            contract.verify_msg(sign3.into(), pubkey.to_string().into(), msg.to_string())
+            contract.verify_msg(
+                sign3.into(),
+                pubkey.to_string().into(),
+                msg.to_string(),
+                None
+            )
         )
         .unwrap_json();
         assert!(!verify3);
@@ -217,7 +272,8 @@ fn test_near_verification() {
             contract.verify_msg(
                 sign.to_string().into(),
                 pubkey4.to_string().into(),
-                msg.to_string()
+                msg.to_string(),
+                None
             )
         )
         .unwrap_json();
@@ -250,7 +306,7 @@ fn test_near_verification() {
             let pubkey = PublicKey::from_bytes(pubkey).unwrap();
             let sign = Signature::from_bytes(&sign).unwrap();
 
-            use ecdsa::signature::Verifier;
+            use ed25519_dalek::Verifier;
             assert!(pubkey.verify(&msg_hash, &sign).is_ok());
         }
     }
@@ -260,3 +316,214 @@ fn test_near_verification() {
 // let expected_hash = "7146bfc46487e635c5dbdc2976dc0cda1eac1c36fbdfe7481ff41c70196b9081";
 // let pubkey = "ed25519:FtB84LCX12AmjovMjXxq86sA8pdbMnixk7aixudPNNuN";
 // let sign = "679dbb85416ee137cdbb8327b630368dbd89fae811ccae47b39ca2082fd62a966ac72c9dabbd55f92d5c1e6a696a7e640e5d60ff10a6428741f04a92746a7e0a";
+
+#[test]
+fn test_verify_batch() {
+    use digest::Digest;
+    use ed25519_dalek::{Keypair, PublicKey, SecretKey, Signer};
+    use nearapps_exec::crypto::{Bs58EncodedSignature, SignedMsg};
+
+    let root = init_simulator(None);
+    let contract = setup_exec(&root);
+
+    // ed25519 entries, as they would be signed by near-api-js
+    let mut entries: Vec<SignedMsg> = (0..8u8)
+        .map(|i| {
+            let secret = SecretKey::from_bytes(&[i + 1; 32]).unwrap();
+            let public = PublicKey::from(&secret);
+            let keypair = Keypair { secret, public };
+            let msg = format!("message {}", i);
+            let msg_hash = sha2::Sha256::new().chain(msg.as_bytes()).finalize();
+            let sign = keypair.sign(&msg_hash);
+            SignedMsg {
+                sign: Bs58EncodedSignature::encode(&sign.to_bytes()),
+                pubkey: format!(
+                    "ed25519:{}",
+                    near_sdk::bs58::encode(public.as_bytes()).into_string()
+                )
+                .into(),
+                msg,
+            }
+        })
+        .collect();
+
+    // a secp256k1 entry
+    {
+        use secp256k1 as s;
+        let secp = s::Secp256k1::new();
+        let seckey = s::SecretKey::from_slice(&[7; 32]).unwrap();
+        let pubkey = s::PublicKey::from_secret_key(&secp, &seckey);
+        let msg = "secp message".to_string();
+        // the msg's hash is hashed again when verifying
+        let msg_hash = sha2::Sha256::new().chain(msg.as_bytes()).finalize();
+        let msg_hash = sha2::Sha256::new().chain(&msg_hash).finalize();
+        let sign = secp.sign_recoverable(&s::Message::from_slice(&msg_hash).unwrap(), &seckey);
+        let (recovery_id, sign) = sign.serialize_compact();
+        let mut sign = sign.to_vec();
+        sign.push(recovery_id.to_i32() as u8);
+        entries.push(SignedMsg {
+            sign: Bs58EncodedSignature::encode(&sign),
+            pubkey: format!(
+                "secp256k1:{}",
+                near_sdk::bs58::encode(&pubkey.serialize_uncompressed()[1..]).into_string()
+            )
+            .into(),
+            msg,
+        });
+    }
+
+    // ok: all entries match
+    let res = call!(&root, contract.verify_batch(entries.clone()));
+    let batch_gas = res.gas_burnt().0;
+    let verified: Vec<bool> = res.unwrap_json();
+    assert_eq!(verified, vec![true; entries.len()]);
+
+    // ok: a single call is cheaper than a call per entry, and it
+    // matches each of them
+    let mut individual_gas = 0;
+    for entry in &entries {
+        let res = call!(
+            &root,
+            contract.verify_msg(
+                entry.sign.clone(),
+                entry.pubkey.clone(),
+                entry.msg.clone(),
+                None
+            )
+        );
+        individual_gas += res.gas_burnt().0;
+        assert!(res.unwrap_json::<bool>());
+    }
+    println!(
+        "batch: {} gas, a call per entry: {} gas",
+        batch_gas, individual_gas
+    );
+    assert!(batch_gas < individual_gas);
+
+    // ok: a single entry
+    let verified: Vec<bool> =
+        call!(&root, contract.verify_batch(entries[..1].to_vec())).unwrap_json();
+    assert_eq!(verified, vec![true]);
+
+    // ok: malformed entries don't match, without failing
+    let mut malformed = entries[..2].to_vec();
+    malformed[0].sign = "not-base58-0OIl".to_string().into();
+    malformed[1].pubkey = "ed25519:abc".to_string().into();
+    let verified: Vec<bool> = call!(&root, contract.verify_batch(malformed)).unwrap_json();
+    assert_eq!(verified, vec![false, false]);
+
+    // fail: only the changed entries don't match
+    let mut bad_entries = entries.clone();
+    bad_entries[2].msg += "0";
+    bad_entries[8].msg += "0";
+    let verified: Vec<bool> = call!(&root, contract.verify_batch(bad_entries)).unwrap_json();
+    let mut expected = vec![true; entries.len()];
+    expected[2] = false;
+    expected[8] = false;
+    assert_eq!(verified, expected);
+}
+
+#[test]
+fn test_eddsa_ed25519_strict() {
+    use nearapps_exec::crypto::eddsa_ed25519::types::{
+        PubKey, PubKeyValidity, SecKey, Sign, VerifyMode,
+    };
+    use nearapps_exec::error::Error;
+    use std::convert::{TryFrom, TryInto};
+
+    let root = init_simulator(None);
+    let contract = setup_exec(&root);
+
+    let verify = |(msg, pubkey, sign): &(&str, &str, &str), mode: VerifyMode| {
+        let pubkey = PubKey(hex(pubkey).try_into().unwrap());
+        let sign = Sign(hex(sign).try_into().unwrap());
+        let res: bool = call!(
+            &root,
+            contract.eddsa_ed25519_verify_bytes(pubkey, sign, hex(msg), Some(mode))
+        )
+        .unwrap_json();
+        res
+    };
+
+    // ok: the only edge case that is strictly valid, with keys and
+    // `R` points of a mixed order
+    for (i, vector) in TAMING_EDDSA_VECTORS.iter().enumerate() {
+        assert_eq!(verify(vector, VerifyMode::Strict), i == 3, "vector {}", i);
+    }
+
+    // ok: the same as ed25519-dalek's `verify_strict()`
+    for (i, (msg, pubkey, sign)) in TAMING_EDDSA_VECTORS.iter().enumerate() {
+        let dalek_strict = ed25519_dalek::PublicKey::from_bytes(&hex(pubkey))
+            .and_then(|pubkey| {
+                let sign = ed25519_dalek::Signature::from_bytes(&hex(sign))?;
+                pubkey.verify_strict(&hex(msg), &sign)
+            })
+            .is_ok();
+        assert_eq!(
+            verify(&TAMING_EDDSA_VECTORS[i], VerifyMode::Strict),
+            dalek_strict,
+            "vector {}",
+            i
+        );
+    }
+
+    // ok: as on Near, small-order keys and `R` points are accepted by
+    // default, and so are non-canonical encodings of `A` and `R`, but
+    // not the cofactored-only signatures nor the unreduced `s`
+    for (i, vector) in TAMING_EDDSA_VECTORS.iter().enumerate() {
+        assert_eq!(
+            verify(vector, VerifyMode::Lenient),
+            matches!(i, 0..=3 | 8 | 11),
+            "vector {}",
+            i
+        );
+    }
+
+    // ok: a regular signature
+    let seckey = SecKey([1; 32]);
+    let pubkey = keys::ed25519_pubkey(seckey.clone());
+    let sign = sign::eddsa_ed25519_sign(seckey, b"message");
+    let res: bool = call!(
+        &root,
+        contract.eddsa_ed25519_verify_msg(
+            pubkey.clone(),
+            sign,
+            "message".to_string(),
+            Some(VerifyMode::Strict)
+        )
+    )
+    .unwrap_json();
+    assert!(res);
+
+    // ok: the keys of the edge cases
+    let validate = |pubkey: PubKey| {
+        let res: PubKeyValidity =
+            call!(&root, contract.eddsa_ed25519_validate_pubkey(pubkey)).unwrap_json();
+        res
+    };
+    let vector_pubkey = |i: usize| PubKey(hex(TAMING_EDDSA_VECTORS[i].1).try_into().unwrap());
+    assert_eq!(validate(pubkey), PubKeyValidity::Valid);
+    assert_eq!(validate(vector_pubkey(0)), PubKeyValidity::SmallOrder);
+    assert_eq!(validate(vector_pubkey(2)), PubKeyValidity::Valid);
+    assert_eq!(validate(vector_pubkey(3)), PubKeyValidity::Valid);
+    assert_eq!(validate(vector_pubkey(10)), PubKeyValidity::NonCanonical);
+
+    // fail: `y = 2` has no matching `x`
+    let mut not_a_point = [0; 32];
+    not_a_point[0] = 2;
+    assert_eq!(validate(PubKey(not_a_point)), PubKeyValidity::NotAPoint);
+    assert_eq!(
+        ed25519_dalek::PublicKey::try_from(PubKey(not_a_point)),
+        Err(Error::InvalidPoint)
+    );
+
+    // fail: and it is not verified in either mode
+    let not_a_point: String = not_a_point.iter().map(|b| format!("{:02x}", b)).collect();
+    let vector = (
+        TAMING_EDDSA_VECTORS[3].0,
+        not_a_point.as_str(),
+        TAMING_EDDSA_VECTORS[3].2,
+    );
+    assert!(!verify(&vector, VerifyMode::Lenient));
+    assert!(!verify(&vector, VerifyMode::Strict));
+}
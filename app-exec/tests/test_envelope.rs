@@ -0,0 +1,118 @@
+#![allow(clippy::ref_in_deref)]
+
+use crate::utils::args::{self, VerifyEnvelopeArgs};
+use crate::utils::keys::{Curve, KeyPair};
+use crate::utils::{hex, setup_exec_at, YOTTA};
+use near_sdk_sim::{call, init_simulator};
+use nearapps_exec::crypto::envelope::{ChainId, Envelope, MAX_CLOCK_SKEW, MAX_VALIDITY};
+use nearapps_exec::crypto::nep413::Nonce;
+
+mod utils;
+
+#[test]
+fn test_envelope() {
+    let root = init_simulator(None);
+    // the chain is from the top-level account of the executor
+    let testnet = root.create_user("testnet".parse().unwrap(), 400 * YOTTA);
+    let contract = setup_exec_at(&root, &testnet, "executor.testnet");
+
+    // ok: the hash is of the tagged borsh serialization
+    let envelope = Envelope {
+        domain: "app.example.com".to_string(),
+        contract_id: "executor".parse().unwrap(),
+        chain_id: ChainId::Testnet,
+        nonce: Nonce([7; 32]),
+        issued_at: 1_000.into(),
+        expires_at: 2_000.into(),
+        payload: "transfer 1 NEAR to bob.near".to_string(),
+    };
+    assert_eq!(
+        envelope.hash().0.to_vec(),
+        hex("566945288414bd3210361fd06d308abb7c89ef9098a3e7f254e774ff7f6295ab")
+    );
+
+    let now = root.borrow_runtime().current_block().block_timestamp;
+    let envelope = Envelope {
+        contract_id: contract.account_id(),
+        issued_at: now.into(),
+        expires_at: (now + 3_600_000_000_000).into(),
+        ..envelope
+    };
+
+    let verify = |args: VerifyEnvelopeArgs| {
+        let res: bool = call!(
+            &root,
+            contract.verify_envelope(args.envelope, args.sign, args.pubkey, args.domain)
+        )
+        .unwrap_json();
+        res
+    };
+
+    // ok: both curves
+    let ed_key = KeyPair::from_seckey(Curve::Ed25519, [1; 32]);
+    let secp_key = KeyPair::from_seckey(Curve::Secp256k1, [2; 32]);
+    let args = args::verify_envelope(&ed_key, envelope.clone());
+    assert!(verify(args.clone()));
+    assert!(verify(args::verify_envelope(&secp_key, envelope.clone())));
+
+    // fail: expected by another app
+    let other_domain = VerifyEnvelopeArgs {
+        domain: "other.example.com".to_string(),
+        ..args.clone()
+    };
+    assert!(!verify(other_domain));
+
+    // fail: signed for another chain
+    let other_chain = Envelope {
+        chain_id: ChainId::Mainnet,
+        ..envelope.clone()
+    };
+    assert!(!verify(args::verify_envelope(&ed_key, other_chain)));
+
+    // fail: signed for another contract
+    let other_contract = Envelope {
+        contract_id: root.account_id(),
+        ..envelope.clone()
+    };
+    assert!(!verify(args::verify_envelope(&ed_key, other_contract)));
+
+    // fail: already expired
+    let expired = Envelope {
+        expires_at: now.into(),
+        ..envelope.clone()
+    };
+    assert!(!verify(args::verify_envelope(&ed_key, expired)));
+
+    // ok: valid for the longest window, issued a bit ahead
+    let longest = Envelope {
+        issued_at: (now + MAX_CLOCK_SKEW).into(),
+        expires_at: (now + MAX_CLOCK_SKEW + MAX_VALIDITY).into(),
+        ..envelope.clone()
+    };
+    assert!(verify(args::verify_envelope(&ed_key, longest)));
+
+    // fail: valid for too long
+    let too_long = Envelope {
+        expires_at: (now + MAX_VALIDITY + 1).into(),
+        ..envelope.clone()
+    };
+    assert!(!verify(args::verify_envelope(&ed_key, too_long)));
+
+    // fail: issued too far ahead, which would extend the window
+    let ahead = Envelope {
+        issued_at: (now + MAX_CLOCK_SKEW + 1).into(),
+        ..envelope
+    };
+    assert!(!verify(args::verify_envelope(&ed_key, ahead)));
+
+    // fail: the payload was changed after signing
+    let mut tampered = args.clone();
+    tampered.envelope.payload = "transfer 100 NEAR to eve.near".to_string();
+    assert!(!verify(tampered));
+
+    // fail: the domain of the envelope was changed after signing
+    let mut rebound = args;
+    rebound.envelope.domain = "other.example.com".to_string();
+    rebound.domain = "other.example.com".to_string();
+    assert!(!verify(rebound));
+}
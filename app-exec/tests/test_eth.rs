@@ -0,0 +1,541 @@
+#![allow(clippy::ref_in_deref)]
+
+use crate::utils::{hex, setup_exec, AssertFailure, TERA};
+use near_sdk::serde_json::json;
+use near_sdk_sim::{call, init_simulator};
+use nearapps_exec::crypto::eth::eip712::{TypedData, Types};
+use nearapps_exec::crypto::eth::types::{Address, ChecksumAddress, SignRsv};
+use nearapps_exec::error::Error;
+use nearapps_exec::hash::Keccak256;
+
+mod utils;
+
+fn address(s: &str) -> Address {
+    let mut res = [0; 20];
+    res.copy_from_slice(&hex(s));
+    Address(res)
+}
+
+fn sign_rsv(s: &str) -> SignRsv {
+    let mut res = [0; 65];
+    res.copy_from_slice(&hex(s));
+    SignRsv(res)
+}
+
+/// The higher-S twin of `sign`, which recovers the same key with the
+/// other recovery id.
+fn high_s(sign: &SignRsv) -> SignRsv {
+    let mut res = sign.clone();
+    let s = k256::Scalar::from_bytes_reduced(k256::FieldBytes::from_slice(&res.0[32..64]));
+    res.0[32..64].copy_from_slice(&(-s).to_bytes());
+    res.0[64] ^= 1;
+    res
+}
+
+// test based on the example from:
+// https://web3js.readthedocs.io/en/v1.2.11/web3-eth-accounts.html#sign
+#[test]
+fn test_eth_personal_sign() {
+    let root = init_simulator(None);
+    let contract = setup_exec(&root);
+
+    let msg = "Some data";
+    let expected_hash = hex("0x1da44b586eb0729ff70a73c326926f6ed5a25f5b056e7f47fbc6e58d86871655");
+    let expected_address = address("0x2c7536E3605D9C16a7a3D7b1898e529396a65c23");
+    let sign = sign_rsv("0xb91467e570a6466aa9e9876cbcd013baba02900b8979d43fe208a4a4f339f5fd6007e74cd82e037b800186422fc2da167c747ef045e5d18a5f5d4300f8e1a0291c");
+
+    // ok: hashes with the prefix
+    let msg_hash: Keccak256 = {
+        let res = call!(&root, contract.eth_personal_sign_hash(msg.to_string()));
+        assert!(res.gas_burnt().0 < 3 * TERA);
+        res.unwrap_json()
+    };
+    assert_eq!(msg_hash.0.to_vec(), expected_hash);
+
+    // ok: recovers the address
+    let recovered: Address = {
+        let res = call!(
+            &root,
+            contract.eth_personal_sign_recover(sign.clone(), msg.to_string())
+        );
+        assert!(res.gas_burnt().0 < 65 * TERA);
+        res.unwrap_json()
+    };
+    assert_eq!(recovered, expected_address);
+
+    // ok: verifies
+    let verify1: bool = call!(
+        &root,
+        contract.verify_eth_personal_sign(expected_address.clone(), sign.clone(), msg.to_string())
+    )
+    .unwrap_json();
+    assert!(verify1);
+
+    // ok: verifies with a non-offset `v`
+    let verify2: bool = {
+        let mut sign = sign.clone();
+        sign.0[64] -= 27;
+        call!(
+            &root,
+            contract.verify_eth_personal_sign(expected_address.clone(), sign, msg.to_string())
+        )
+        .unwrap_json()
+    };
+    assert!(verify2);
+
+    // fail: different message
+    let verify3: bool = call!(
+        &root,
+        contract.verify_eth_personal_sign(
+            expected_address.clone(),
+            sign.clone(),
+            msg.to_string() + "0"
+        )
+    )
+    .unwrap_json();
+    assert!(!verify3);
+
+    // fail: the higher-S twin
+    let high_s = high_s(&sign);
+    let verify5: bool = call!(
+        &root,
+        contract.verify_eth_personal_sign(
+            expected_address.clone(),
+            high_s.clone(),
+            msg.to_string()
+        )
+    )
+    .unwrap_json();
+    assert!(!verify5);
+    let res = call!(
+        &root,
+        contract.eth_personal_sign_recover(high_s, msg.to_string())
+    );
+    res.assert_failure(0, Error::RecoverFailed);
+
+    // fail: invalid `v`
+    let verify4: bool = {
+        let mut sign = sign;
+        sign.0[64] = 29;
+        call!(
+            &root,
+            contract.verify_eth_personal_sign(expected_address, sign, msg.to_string())
+        )
+        .unwrap_json()
+    };
+    assert!(!verify4);
+}
+
+// test based on the example from:
+// https://github.com/ethereum/EIPs/blob/master/assets/eip-712/Example.js
+#[test]
+fn test_eth_eip712() {
+    let root = init_simulator(None);
+    let contract = setup_exec(&root);
+
+    let types: Types = near_sdk::serde_json::from_value(json!({
+        "EIP712Domain": [
+            {"name": "name", "type": "string"},
+            {"name": "version", "type": "string"},
+            {"name": "chainId", "type": "uint256"},
+            {"name": "verifyingContract", "type": "address"},
+        ],
+        "Person": [
+            {"name": "name", "type": "string"},
+            {"name": "wallet", "type": "address"},
+        ],
+        "Mail": [
+            {"name": "from", "type": "Person"},
+            {"name": "to", "type": "Person"},
+            {"name": "contents", "type": "string"},
+        ],
+    }))
+    .unwrap();
+    let domain = json!({
+        "name": "Ether Mail",
+        "version": "1",
+        "chainId": 1,
+        "verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC",
+    });
+    let message = json!({
+        "from": {"name": "Cow", "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"},
+        "to": {"name": "Bob", "wallet": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB"},
+        "contents": "Hello, Bob!",
+    });
+    let expected_domain_separator =
+        hex("0xf2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f");
+    let expected_struct_hash =
+        hex("0xc52c0ee5d84264471806290a3f2c4cecfc5490626bf912d01f240d7a274b371e");
+    let expected_hash = hex("0xbe609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2");
+    let expected_address = address("0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826");
+    let sign = sign_rsv("0x4355c47d63924e8a72e509b65029052eb6c299d53a04e167c5775fd466751c9d07299936d304c153f6443dfa05f40ff007d72911b6f72307f996231605b915621c");
+
+    // ok: hashes the domain
+    let domain_separator: Keccak256 = call!(
+        &root,
+        contract.eip712_hash_struct(types.clone(), "EIP712Domain".to_string(), domain)
+    )
+    .unwrap_json();
+    assert_eq!(domain_separator.0.to_vec(), expected_domain_separator);
+
+    // ok: hashes the message
+    let struct_hash: Keccak256 = call!(
+        &root,
+        contract.eip712_hash_struct(types.clone(), "Mail".to_string(), message.clone())
+    )
+    .unwrap_json();
+    assert_eq!(struct_hash.0.to_vec(), expected_struct_hash);
+
+    let typed_data = TypedData {
+        domain_separator,
+        types,
+        primary_type: "Mail".to_string(),
+        message,
+    };
+
+    // ok: creates the signing hash
+    let msg_hash: Keccak256 = {
+        let res = call!(&root, contract.eip712_signing_hash(typed_data.clone()));
+        assert!(res.gas_burnt().0 < 10 * TERA);
+        res.unwrap_json()
+    };
+    assert_eq!(msg_hash.0.to_vec(), expected_hash);
+
+    // ok: also creates the signing hash off-chain
+    let msg_hash = nearapps_exec::crypto::eth::eip712::signing_hash(&typed_data);
+    assert_eq!(msg_hash.0.to_vec(), expected_hash);
+
+    // ok: recovers the address
+    let recovered: Address = {
+        let res = call!(
+            &root,
+            contract.eip712_recover(typed_data.clone(), sign.clone())
+        );
+        assert!(res.gas_burnt().0 < 70 * TERA);
+        res.unwrap_json()
+    };
+    assert_eq!(recovered, expected_address);
+
+    // ok: verifies
+    let verify1: bool = call!(
+        &root,
+        contract.verify_eip712(expected_address.clone(), sign.clone(), typed_data.clone())
+    )
+    .unwrap_json();
+    assert!(verify1);
+
+    // fail: different contents
+    let verify2: bool = {
+        let mut typed_data = typed_data.clone();
+        typed_data.message["contents"] = json!("Hello, Bob?");
+        call!(
+            &root,
+            contract.verify_eip712(expected_address.clone(), sign.clone(), typed_data)
+        )
+        .unwrap_json()
+    };
+    assert!(!verify2);
+
+    // fail: the higher-S twin
+    let high_s = high_s(&sign);
+    let verify3: bool = call!(
+        &root,
+        contract.verify_eip712(expected_address.clone(), high_s.clone(), typed_data.clone())
+    )
+    .unwrap_json();
+    assert!(!verify3);
+    let res = call!(&root, contract.eip712_recover(typed_data.clone(), high_s));
+    res.assert_failure(0, Error::RecoverFailed);
+
+    // fail: missing field
+    {
+        let mut typed_data = typed_data;
+        typed_data.message.as_object_mut().unwrap().remove("to");
+        let res = call!(
+            &root,
+            contract.verify_eip712(expected_address, sign, typed_data)
+        );
+        res.assert_failure(0, "ERR_EXEC_EIP712_MISSING_FIELD");
+    }
+}
+
+#[test]
+fn test_eth_eip712_ints() {
+    let root = init_simulator(None);
+    let contract = setup_exec(&root);
+
+    let hash = |type_: &str, value: near_sdk::serde_json::Value| {
+        let types: Types = near_sdk::serde_json::from_value(json!({
+            "Value": [{"name": "value", "type": type_}],
+        }))
+        .unwrap();
+        call!(
+            &root,
+            contract.eip712_hash_struct(types, "Value".to_string(), json!({ "value": value }))
+        )
+    };
+    let encoded = |type_: &str, value: near_sdk::serde_json::Value| {
+        let mut bytes = Keccak256::hash_bytes(format!("Value({} value)", type_).as_bytes())
+            .0
+            .to_vec();
+        bytes.extend(hex(&format!("{:0>64}", value.as_str().unwrap())));
+        Keccak256::hash_bytes(&bytes)
+    };
+
+    // ok: the bounds of each type
+    for (type_, value, expected) in [
+        ("uint8", json!(255), json!("ff")),
+        ("uint8", json!("0xff"), json!("ff")),
+        (
+            "uint256",
+            json!(format!("0x{}", "ff".repeat(32))),
+            json!("ff".repeat(32)),
+        ),
+        ("int8", json!(127), json!("7f")),
+        ("int8", json!(-128), json!("f".repeat(62) + "80")),
+        ("int16", json!("-1"), json!("f".repeat(64))),
+        ("uint32", json!("-0"), json!("0")),
+        // an odd number of hex digits
+        ("uint8", json!("0x1"), json!("1")),
+        ("uint16", json!("0xfff"), json!("fff")),
+        ("int16", json!("-0x1"), json!("f".repeat(64))),
+    ] {
+        let res: Keccak256 = hash(type_, value).unwrap_json();
+        assert_eq!(res, encoded(type_, expected), "{}", type_);
+    }
+
+    // fail: out of the range of the type, or negative unsigned
+    for (type_, value) in [
+        ("uint8", json!(256)),
+        ("uint8", json!(-1)),
+        ("uint256", json!("-1")),
+        ("int8", json!(128)),
+        ("int8", json!(-129)),
+        ("uint8", json!("+1")),
+        ("uint8", json!("0x+1")),
+    ] {
+        hash(type_, value.clone()).assert_failure(0, "ERR_EXEC_EIP712_BAD_VALUE");
+    }
+
+    // fail: not an EIP-712 type
+    for type_ in ["uint", "uint7", "int264", "uint+8", "bytes0", "bytes33"] {
+        hash(type_, json!(1)).assert_failure(0, "ERR_EXEC_EIP712_UNKNOWN_TYPE");
+    }
+
+    // ok: arrays are the hash of their encoded items
+    let items = || {
+        let mut bytes = hex(&format!("{:0>64}", "1"));
+        bytes.extend(hex(&format!("{:0>64}", "2")));
+        Keccak256::hash_bytes(&bytes).0
+    };
+    let array_hash = |type_: &str| {
+        let mut bytes = Keccak256::hash_bytes(format!("Value({} value)", type_).as_bytes())
+            .0
+            .to_vec();
+        bytes.extend(items());
+        Keccak256::hash_bytes(&bytes)
+    };
+    for type_ in ["uint256[]", "uint256[2]"] {
+        let res: Keccak256 = hash(type_, json!([1, "0x2"])).unwrap_json();
+        assert_eq!(res, array_hash(type_), "{}", type_);
+    }
+
+    // fail: a fixed-size array with another number of items
+    for value in [json!([]), json!([1]), json!([1, 2, 3])] {
+        hash("uint256[2]", value).assert_failure(0, "ERR_EXEC_EIP712_BAD_VALUE");
+    }
+
+    // fail: not an array size
+    hash("uint256[+2]", json!([1, 2])).assert_failure(0, "ERR_EXEC_EIP712_UNKNOWN_TYPE");
+}
+
+#[test]
+fn test_eth_address() {
+    use crate::utils::args::{self, VerifyByEthAddressArgs};
+    use crate::utils::keys::{self, Curve, KeyPair};
+    use nearapps_exec::crypto::{Bs58EncodedSignature, NearEncodedPubkey};
+    use std::convert::TryInto;
+
+    let root = init_simulator(None);
+    let contract = setup_exec(&root);
+
+    // ok: the checksums from EIP-55
+    for checksummed in [
+        "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+        "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+        "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+        "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+    ] {
+        let parsed = ChecksumAddress(checksummed.to_string()).parse().unwrap();
+        assert_eq!(parsed, address(checksummed));
+        assert_eq!(ChecksumAddress::from(parsed.clone()).0, checksummed);
+
+        // ok: without a checksum
+        let lowercase = format!("0x{}", checksummed[2..].to_lowercase());
+        assert_eq!(ChecksumAddress(lowercase).parse(), Some(parsed.clone()));
+        let uppercase = format!("0x{}", checksummed[2..].to_uppercase());
+        assert_eq!(ChecksumAddress(uppercase).parse(), Some(parsed));
+    }
+
+    // fail: a letter in the wrong case, or malformed
+    let wrong_case = ChecksumAddress("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD".to_string());
+    assert_eq!(wrong_case.parse(), None);
+    let no_prefix = ChecksumAddress("5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed".to_string());
+    assert_eq!(no_prefix.parse(), None);
+    let too_short = ChecksumAddress("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeA".to_string());
+    assert_eq!(too_short.parse(), None);
+    let double_prefix = ChecksumAddress("0x0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed".to_string());
+    assert_eq!(double_prefix.parse(), None);
+
+    // ok: addresses are json hex strings, with the checksum
+    let parsed = address("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed");
+    let json = near_sdk::serde_json::to_string(&parsed).unwrap();
+    assert_eq!(json, r#""0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed""#);
+    let lowercase = r#""0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed""#;
+    assert_eq!(
+        near_sdk::serde_json::from_str::<Address>(lowercase).unwrap(),
+        parsed
+    );
+    let wrong_case_json = r#""0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD""#;
+    assert!(near_sdk::serde_json::from_str::<Address>(wrong_case_json).is_err());
+
+    // ok: the address of the secret key `1`, from any pubkey form
+    let mut seckey = [0; 32];
+    seckey[31] = 1;
+    let key = KeyPair::from_seckey(Curve::Secp256k1, seckey);
+    let expected = ChecksumAddress("0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf".to_string());
+    assert_eq!(
+        keys::secp256k1_eth_address(key.secp256k1_seckey()),
+        expected
+    );
+
+    let uncompressed = keys::secp256k1_pubkey_uncompressed(key.secp256k1_seckey());
+    let compressed = keys::secp256k1_pubkey_compressed(key.secp256k1_seckey());
+    for pubkey in [
+        key.near_public_key(),
+        NearEncodedPubkey(format!(
+            "secp256k1:{}",
+            near_sdk::bs58::encode(uncompressed.0).into_string()
+        )),
+        NearEncodedPubkey(format!(
+            "secp256k1:{}",
+            near_sdk::bs58::encode(compressed.0).into_string()
+        )),
+    ] {
+        let res: ChecksumAddress = call!(&root, contract.eth_address(pubkey)).unwrap_json();
+        assert_eq!(res, expected);
+    }
+
+    // fail: not a secp256k1 key
+    let ed_key = KeyPair::from_seckey(Curve::Ed25519, [1; 32]);
+    let res = call!(&root, contract.eth_address(ed_key.near_public_key()));
+    res.assert_failure(0, Error::UnknownCurve);
+
+    let verify = |args: &VerifyByEthAddressArgs| {
+        let res: bool = call!(
+            &root,
+            contract.verify_by_eth_address(
+                args.address.clone(),
+                args.sign.clone(),
+                args.msg.clone()
+            )
+        )
+        .unwrap_json();
+        res
+    };
+
+    // ok: signed with `personal_sign`
+    let msg = "This is some message".to_string();
+    let secp_key = KeyPair::from_seckey(Curve::Secp256k1, [2; 32]);
+    let args = args::verify_by_eth_address(&secp_key, msg.clone());
+    assert!(verify(&args));
+    let personal_sign: bool = call!(
+        &root,
+        contract.verify_eth_personal_sign(
+            args.address.parse().unwrap(),
+            SignRsv(args.sign.decode().try_into().unwrap()),
+            msg
+        )
+    )
+    .unwrap_json();
+    assert!(personal_sign);
+
+    // ok: recovers the address of web3's key, from the example of
+    // `test_eth_personal_sign`
+    let web3_key = KeyPair::from_seckey(
+        Curve::Secp256k1,
+        hex("0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318")
+            .try_into()
+            .unwrap(),
+    );
+    let web3 = args::verify_by_eth_address(&web3_key, "Some data".to_string());
+    let recovered: Address = call!(
+        &root,
+        contract.eth_personal_sign_recover(
+            SignRsv(web3.sign.decode().try_into().unwrap()),
+            web3.msg.clone()
+        )
+    )
+    .unwrap_json();
+    assert_eq!(
+        recovered,
+        address("0x2c7536E3605D9C16a7a3D7b1898e529396a65c23")
+    );
+    assert!(verify(&web3));
+
+    // ok: the address without a checksum
+    let lowercase = VerifyByEthAddressArgs {
+        address: ChecksumAddress(args.address.0.to_lowercase()),
+        ..args.clone()
+    };
+    assert!(verify(&lowercase));
+
+    // ok: with `v` offset by 27, as Ethereum has it
+    let mut sign = args.sign.decode();
+    sign[64] += 27;
+    let offset = VerifyByEthAddressArgs {
+        sign: Bs58EncodedSignature::encode(&sign),
+        ..args.clone()
+    };
+    assert!(verify(&offset));
+
+    // fail: another address, or another msg
+    let other_address = VerifyByEthAddressArgs {
+        address: expected,
+        ..args.clone()
+    };
+    assert!(!verify(&other_address));
+    let other_msg = VerifyByEthAddressArgs {
+        msg: "This is another message".to_string(),
+        ..args.clone()
+    };
+    assert!(!verify(&other_msg));
+
+    // fail: the higher-S twin, which recovers the same key with the
+    // other recovery id
+    let mut sign = [0; 65];
+    sign.copy_from_slice(&args.sign.decode());
+    let high_s = VerifyByEthAddressArgs {
+        sign: Bs58EncodedSignature::encode(&high_s(&SignRsv(sign)).0),
+        ..args.clone()
+    };
+    assert!(!verify(&high_s));
+
+    // fail: the checksum doesn't match
+    let res = call!(
+        &root,
+        contract.verify_by_eth_address(wrong_case, args.sign.clone(), args.msg.clone())
+    );
+    res.assert_failure(0, Error::BadEthAddress);
+
+    // fail: without the recovery byte
+    let res = call!(
+        &root,
+        contract.verify_by_eth_address(
+            args.address,
+            Bs58EncodedSignature::encode(&args.sign.decode()[..64]),
+            args.msg
+        )
+    );
+    res.assert_failure(0, Error::WrongSignatureLength);
+}
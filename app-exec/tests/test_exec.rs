@@ -1,10 +1,11 @@
 #![allow(clippy::ref_in_deref)]
 #![allow(clippy::needless_borrow)]
 
-use crate::utils::{setup_counter, setup_exec};
+use crate::utils::{setup_counter, setup_exec, AssertFailure, YOTTA};
 use crypto::eddsa_ed25519 as ed;
-use near_sdk_sim::{call, init_simulator};
+use near_sdk_sim::{call, init_simulator, view};
 use nearapps_exec::crypto;
+use nearapps_exec::error::Error;
 use nearapps_exec::exec::{CallContext, ContractCall, TagInfo};
 
 mod utils;
@@ -73,3 +74,360 @@ fn test_exec_basic() {
     let val: u8 = res.unwrap_json();
     assert_eq!(val, 2);
 }
+
+#[test]
+fn test_exec_validate() {
+    let root = init_simulator(None);
+    let exec = setup_exec(&root);
+    let counter = setup_counter(&root);
+
+    let ctx = |contract_id: near_sdk::AccountId| {
+        into_callctx(ContractCall {
+            contract_id,
+            method_name: "increment".into(),
+            args: "".into(),
+        })
+    };
+
+    // ok: the owner can execute on the counter
+    let violations: Vec<String> =
+        view!(exec.validate_execution(ctx(counter.account_id()), root.account_id(), None, None))
+            .unwrap_json();
+    assert!(violations.is_empty());
+
+    // fail: not an owner
+    let violations: Vec<String> = view!(exec.validate_execution(
+        ctx(counter.account_id()),
+        "user.id".parse().unwrap(),
+        None,
+        None
+    ))
+    .unwrap_json();
+    assert_eq!(violations, vec!["NotOwner"]);
+
+    // fail: not an owner and calling into the executor itself
+    let violations: Vec<String> = view!(exec.validate_execution(
+        ctx(exec.account_id()),
+        "user.id".parse().unwrap(),
+        None,
+        None
+    ))
+    .unwrap_json();
+    assert_eq!(violations, vec!["NotOwner", "CallCurrentAccount"]);
+
+    // ok: validation didn't change the counter
+    let val: u8 = view!(counter.get()).unwrap_json();
+    assert_eq!(val, 0);
+}
+
+#[test]
+fn test_exec_stats() {
+    use nearapps_exec::stats::{AppUser, ExecutionStats};
+
+    let root = init_simulator(None);
+    let exec = setup_exec(&root);
+    let counter = setup_counter(&root);
+
+    let ctx = |method_name: &str, args: &str| {
+        into_callctx(ContractCall {
+            contract_id: counter.account_id(),
+            method_name: method_name.into(),
+            args: args.into(),
+        })
+    };
+
+    // ok: enables the per-user stats (disabled by default)
+    let res = call!(&root, exec.set_user_stats_tracking(true));
+    res.assert_success();
+
+    // ok: success, forwarding some deposit
+    let res = call!(
+        &root,
+        exec.execute(ctx("deposit", r#"{"increment": true}"#)),
+        deposit = 3
+    );
+    let val: u8 = res.unwrap_json();
+    assert_eq!(val, 3);
+
+    // fail: the counter panics, and the failure is forwarded from a
+    // fail_promise receipt after it's recorded
+    let res = call!(
+        &root,
+        exec.execute(ctx("withdraw", r#"{"qty": 4, "decrement": true}"#))
+    );
+    res.assert_failure(0, "Promise with index 0 failed");
+    let val: u8 = view!(counter.get()).unwrap_json();
+    assert_eq!(val, 3);
+
+    let stats: Option<ExecutionStats> =
+        view!(exec.get_app_stats("the_app_id".into())).unwrap_json();
+    let stats = stats.unwrap();
+    assert_eq!(stats.executions.0, 2);
+    assert_eq!(stats.successes.0, 1);
+    assert_eq!(stats.failures.0, 1);
+    assert_eq!(stats.deposit.0, 3);
+    assert!(stats.last_activity.0 > 0);
+
+    let user_stats: Option<ExecutionStats> =
+        view!(exec.get_user_stats("the_app_id".into(), "user.id".parse().unwrap())).unwrap_json();
+    assert_eq!(user_stats, Some(stats.clone()));
+
+    // ok: pagination
+    let apps: Vec<(String, ExecutionStats)> = view!(exec.get_apps_stats(None, None)).unwrap_json();
+    assert_eq!(apps, vec![("the_app_id".to_string(), stats)]);
+    let apps: Vec<(String, ExecutionStats)> =
+        view!(exec.get_apps_stats(Some(1.into()), Some(10))).unwrap_json();
+    assert!(apps.is_empty());
+
+    // ok: resets
+    let res = call!(&root, exec.reset_app_stats("the_app_id".into()));
+    let removed: bool = res.unwrap_json();
+    assert!(removed);
+    let stats: Option<ExecutionStats> =
+        view!(exec.get_app_stats("the_app_id".into())).unwrap_json();
+    assert!(stats.is_none());
+
+    let res = call!(&root, exec.reset_stats());
+    res.assert_success();
+    let users: Vec<(AppUser, ExecutionStats)> =
+        view!(exec.get_users_stats(None, None)).unwrap_json();
+    assert!(users.is_empty());
+}
+
+#[test]
+fn test_exec_relayer_keys() {
+    use near_sdk::json_types::U128;
+    use nearapps_exec::relayer::RelayerKey;
+
+    let root = init_simulator(None);
+    let exec = setup_exec(&root);
+    let counter = setup_counter(&root);
+
+    let pubkey: near_sdk::PublicKey = "ed25519:AYWv9RAN1hpSQA4p1DLhCNnpnNXwxhfH9qeHN8B4nJ59"
+        .parse()
+        .unwrap();
+    let key = RelayerKey {
+        label: "backend-1".into(),
+    };
+    let allowance: U128 = (YOTTA / 10).into();
+
+    // fail: a zero allowance would be unlimited
+    let res = call!(
+        &root,
+        exec.add_relayer_key(pubkey.clone(), 0.into(), key.label.clone())
+    );
+    res.assert_failure(0, Error::ZeroAllowance);
+
+    // ok: adds the key
+    let res = call!(
+        &root,
+        exec.add_relayer_key(pubkey.clone(), allowance, key.label.clone())
+    );
+    res.assert_success();
+
+    // fail: the key was already added
+    let res = call!(
+        &root,
+        exec.add_relayer_key(pubkey.clone(), allowance, key.label.clone())
+    );
+    res.assert_failure(0, Error::RelayerKeyAlreadyAdded);
+
+    let keys: Vec<(near_sdk::PublicKey, RelayerKey)> =
+        view!(exec.get_relayer_keys(None, None)).unwrap_json();
+    assert_eq!(keys, vec![(pubkey.clone(), key)]);
+
+    // ok: calls made with the key have the executor as the caller
+    let ctx = into_callctx(ContractCall {
+        contract_id: counter.account_id(),
+        method_name: "increment".into(),
+        args: "".into(),
+    });
+    let violations: Vec<String> =
+        view!(exec.validate_execution(ctx.clone(), exec.account_id(), None, Some(pubkey.clone())))
+            .unwrap_json();
+    assert!(violations.is_empty());
+
+    // fail: other keys of the executor are not relayer keys
+    let other: near_sdk::PublicKey = "ed25519:6E8sCci9badyRkXb3JoRpBj5p8C6Tw41ELDZoiihKEtp"
+        .parse()
+        .unwrap();
+    let violations: Vec<String> =
+        view!(exec.validate_execution(ctx, exec.account_id(), None, Some(other))).unwrap_json();
+    assert_eq!(violations, vec!["NotOwner"]);
+
+    // ok: removes the key
+    let res = call!(&root, exec.remove_relayer_key(pubkey.clone()));
+    res.assert_success();
+    let key: Option<RelayerKey> = view!(exec.get_relayer_key(pubkey.clone())).unwrap_json();
+    assert!(key.is_none());
+
+    // fail: the key was already removed
+    let res = call!(&root, exec.remove_relayer_key(pubkey));
+    res.assert_failure(0, Error::MissingRelayerKey);
+}
+
+#[test]
+fn test_exec_spending_cap() {
+    use nearapps_exec::spending::{RemainingAllowance, SpendingCap};
+
+    let root = init_simulator(None);
+    let exec = setup_exec(&root);
+    let counter = setup_counter(&root);
+
+    let ctx = into_callctx(ContractCall {
+        contract_id: counter.account_id(),
+        method_name: "deposit".into(),
+        args: r#"{"increment": true}"#.into(),
+    });
+    let app_id = || ctx.tag_info.app_id.clone();
+    let user_id = || ctx.tag_info.user_id.clone();
+
+    let res = call!(
+        &root,
+        exec.set_spending_cap(
+            app_id(),
+            Some(SpendingCap {
+                lifetime: Some(10.into()),
+                daily: Some(5.into()),
+            })
+        )
+    );
+    res.assert_success();
+
+    // ok: within the caps
+    let res = call!(&root, exec.execute(ctx.clone()), deposit = 4);
+    let val: u8 = res.unwrap_json();
+    assert_eq!(val, 4);
+
+    let remaining: RemainingAllowance =
+        view!(exec.get_remaining_allowance(app_id(), user_id())).unwrap_json();
+    assert_eq!(
+        remaining,
+        RemainingAllowance {
+            lifetime: Some(6.into()),
+            daily: Some(1.into()),
+        }
+    );
+
+    // fail: the counter panics, so the deposit is refunded and it
+    // doesn't count towards the caps
+    let failing = CallContext {
+        contract_call: ContractCall {
+            args: "{}".into(),
+            ..ctx.contract_call.clone()
+        },
+        ..ctx.clone()
+    };
+    let res = call!(&root, exec.execute(failing), deposit = 1);
+    res.assert_failure(0, "Promise with index 0 failed");
+    let remaining: RemainingAllowance =
+        view!(exec.get_remaining_allowance(app_id(), user_id())).unwrap_json();
+    assert_eq!(
+        remaining,
+        RemainingAllowance {
+            lifetime: Some(6.into()),
+            daily: Some(1.into()),
+        }
+    );
+
+    // fail: over the daily cap
+    let violations: Vec<String> =
+        view!(exec.validate_execution(ctx.clone(), root.account_id(), Some(2.into()), None))
+            .unwrap_json();
+    assert_eq!(violations, vec!["DailySpendingCapExceeded"]);
+
+    let res = call!(&root, exec.execute(ctx.clone()), deposit = 2);
+    res.assert_failure(0, Error::DailySpendingCapExceeded);
+
+    // ok: the counter wasn't called
+    let val: u8 = view!(counter.get()).unwrap_json();
+    assert_eq!(val, 4);
+
+    // ok: without a cap there are no limits
+    let res = call!(&root, exec.set_spending_cap(app_id(), None));
+    res.assert_success();
+    let remaining: RemainingAllowance =
+        view!(exec.get_remaining_allowance(app_id(), user_id())).unwrap_json();
+    assert_eq!(
+        remaining,
+        RemainingAllowance {
+            lifetime: None,
+            daily: None,
+        }
+    );
+}
+
+#[test]
+fn test_exec_audit_log() {
+    use nearapps_exec::audit::AuditEntry;
+    use nearapps_exec::hash::merkle::{verify_proof, MerkleNode, MerkleProof};
+
+    let root = init_simulator(None);
+    let exec = setup_exec(&root);
+    let counter = setup_counter(&root);
+
+    let ctx = |method_name: &str| {
+        into_callctx(ContractCall {
+            contract_id: counter.account_id(),
+            method_name: method_name.into(),
+            args: "".into(),
+        })
+    };
+
+    // ok: the log starts empty
+    let audit_root: MerkleNode = view!(exec.get_audit_root()).unwrap_json();
+    assert_eq!(audit_root, MerkleNode::default());
+
+    // ok: each execution logs its entry, with its outcome, once the
+    // contract call is done
+    let mut entries: Vec<AuditEntry> = vec![];
+    for method_name in [
+        "increment",
+        "increment",
+        "decrement",
+        "decrement",
+        "decrement",
+    ] {
+        let res = call!(&root, exec.execute(ctx(method_name)));
+        let entry = res
+            .promise_results()
+            .into_iter()
+            .flatten()
+            .flat_map(|res| res.logs().clone())
+            .find_map(|log| near_sdk::serde_json::from_str(&log).ok())
+            .unwrap();
+        entries.push(entry);
+    }
+    assert_eq!(entries[1].seq.0, 1);
+    assert!(entries[3].success);
+    // the counter can't go below zero
+    assert!(!entries[4].success);
+
+    let leaf = |entry: &AuditEntry| MerkleNode::from(entry.leaf());
+
+    // ok: proves every entry against the current root, with one
+    // sibling per level
+    let audit_root: MerkleNode = view!(exec.get_audit_root()).unwrap_json();
+    for entry in &entries {
+        let proof: MerkleProof = view!(exec.get_audit_proof(entry.seq, None)).unwrap_json();
+        assert_eq!(proof.siblings.len(), 3);
+        assert!(verify_proof(&audit_root, &leaf(entry), &proof));
+    }
+
+    // ok: proves the second entry against the root right after the
+    // third was appended
+    let proof: MerkleProof = view!(exec.get_audit_proof(1.into(), Some(2.into()))).unwrap_json();
+    let root_at: MerkleNode = view!(exec.get_audit_root_at(2.into())).unwrap_json();
+    assert_eq!(proof.siblings.len(), 2);
+    assert!(verify_proof(&root_at, &leaf(&entries[1]), &proof));
+    assert!(!verify_proof(&audit_root, &leaf(&entries[1]), &proof));
+
+    // fail: a tampered entry doesn't match
+    let mut tampered = entries[1].clone();
+    tampered.success = false;
+    assert!(!verify_proof(&root_at, &leaf(&tampered), &proof));
+
+    // fail: past the end of the log
+    let res = view!(exec.get_audit_proof(5.into(), None));
+    assert!(res.is_err());
+}
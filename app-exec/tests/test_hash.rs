@@ -0,0 +1,103 @@
+#![allow(clippy::ref_in_deref)]
+
+use crate::utils::{hex, setup_exec, TERA};
+use near_sdk_sim::{call, init_simulator};
+use nearapps_exec::hash;
+
+mod utils;
+
+#[test]
+fn test_hash() {
+    let root = init_simulator(None);
+    let contract = setup_exec(&root);
+
+    let msg = "abc";
+
+    // ok: keccak256
+    let keccak256: hash::Keccak256 =
+        call!(&root, contract.hash_keccak256_msg(msg.to_string())).unwrap_json();
+    assert_eq!(
+        keccak256.0.to_vec(),
+        hex("4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45")
+    );
+
+    // ok: sha3-256
+    let sha3_256: hash::Sha3_256 =
+        call!(&root, contract.hash_sha3_256_msg(msg.to_string())).unwrap_json();
+    assert_eq!(
+        sha3_256.0.to_vec(),
+        hex("3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532")
+    );
+
+    // ok: ripemd160
+    let ripemd160: hash::Ripemd160 =
+        call!(&root, contract.hash_ripemd160_msg(msg.to_string())).unwrap_json();
+    assert_eq!(
+        ripemd160.0.to_vec(),
+        hex("8eb208f7e05d987a9b044a8e98c6b087f15a0bfc")
+    );
+
+    // ok: blake2b-256
+    let blake2b256: hash::Blake2b256 =
+        call!(&root, contract.hash_blake2b256_msg(msg.to_string())).unwrap_json();
+    assert_eq!(
+        blake2b256.0.to_vec(),
+        hex("bddd813c634239723171ef3fee98579b94964e3bb1cb3e427262c8c068d52319")
+    );
+
+    // ok: hash160
+    let hash160: hash::Hash160 =
+        call!(&root, contract.hash_hash160_msg(msg.to_string())).unwrap_json();
+    assert_eq!(
+        hash160.0.to_vec(),
+        hex("bb1be98c142444d7a56aa3981c3942a978e4dc33")
+    );
+
+    // ok: the bytes and the msg variants agree
+    let hash160_bytes: hash::Hash160 =
+        call!(&root, contract.hash_hash160(msg.as_bytes().to_vec())).unwrap_json();
+    assert_eq!(hash160_bytes, hash160);
+}
+
+/// Compares the gas of the hashes that use a host function against
+/// the ones that are computed inside of wasm.
+#[test]
+fn test_hash_gas() {
+    let root = init_simulator(None);
+    let contract = setup_exec(&root);
+
+    let msg_bytes = "abc".repeat(1000).into_bytes();
+
+    // host function
+    let keccak256_gas = call!(&root, contract.hash_keccak256(msg_bytes.clone()))
+        .gas_burnt()
+        .0;
+    // host function
+    let sha256_gas = call!(&root, contract.hash_sha256(msg_bytes.clone()))
+        .gas_burnt()
+        .0;
+    // wasm
+    let sha3_256_gas = call!(&root, contract.hash_sha3_256(msg_bytes.clone()))
+        .gas_burnt()
+        .0;
+    // wasm
+    let blake2b256_gas = call!(&root, contract.hash_blake2b256(msg_bytes.clone()))
+        .gas_burnt()
+        .0;
+    // wasm
+    let ripemd160_gas = call!(&root, contract.hash_ripemd160(msg_bytes))
+        .gas_burnt()
+        .0;
+
+    println!(
+        "gas burnt: keccak256 {}, sha256 {}, sha3-256 {}, blake2b-256 {}, ripemd160 {}",
+        keccak256_gas, sha256_gas, sha3_256_gas, blake2b256_gas, ripemd160_gas
+    );
+
+    // keccak256 and sha3-256 only differ on padding, so the host
+    // function is what makes the difference
+    assert!(keccak256_gas < sha3_256_gas);
+    assert!(sha256_gas < sha3_256_gas);
+    assert!(keccak256_gas < 3 * TERA);
+    assert!(sha256_gas < 3 * TERA);
+}
@@ -0,0 +1,197 @@
+#![allow(clippy::ref_in_deref)]
+
+use crate::utils::{hex, setup_exec};
+use near_sdk::serde_json::{self, Value};
+use near_sdk_sim::{call, init_simulator};
+use nearapps_exec::hash::merkle::{MerkleHash, MerkleNode, MerkleProof, Pairing};
+use std::convert::TryInto;
+
+mod utils;
+
+/// A tree in OpenZeppelin's `StandardMerkleTree` of
+/// `["address", "uint256"]` values, with the leaves in the tree order
+/// and the proofs as `getProof()` returns them.
+const OPENZEPPELIN_TREE: &str = include_str!("data/openzeppelin_merkle_tree.json");
+
+fn node(s: &str) -> MerkleNode {
+    MerkleNode(hex(s).try_into().unwrap())
+}
+
+/// The leaf that OpenZeppelin's `StandardMerkleTree` has for an
+/// `["address", "uint256"]` value, which is the `keccak256` of the
+/// `keccak256` of the abi encoded value.
+fn openzeppelin_leaf(address: &str, amount: &str) -> MerkleNode {
+    let mut encoded = vec![0; 12];
+    encoded.extend(hex(address));
+    encoded.extend(&[0; 16]);
+    encoded.extend(&amount.parse::<u128>().unwrap().to_be_bytes());
+    let keccak = MerkleHash::Keccak256;
+    keccak.hash_leaf(&keccak.hash_leaf(&encoded).0)
+}
+
+/// The levels of a tree of indexed pairs, from the leaves up to the
+/// root, with the leaves padded with zeroed nodes up to a power of two.
+fn indexed_levels(hash: MerkleHash, mut leaves: Vec<MerkleNode>) -> Vec<Vec<MerkleNode>> {
+    leaves.resize(leaves.len().next_power_of_two(), MerkleNode::default());
+    let mut levels = vec![leaves];
+    while levels.last().unwrap().len() > 1 {
+        let parents = levels
+            .last()
+            .unwrap()
+            .chunks(2)
+            .map(|pair| hash.hash_pair(&pair[0], &pair[1]))
+            .collect();
+        levels.push(parents);
+    }
+    levels
+}
+
+#[test]
+fn test_merkle_openzeppelin() {
+    let root = init_simulator(None);
+    let contract = setup_exec(&root);
+
+    let verify = |merkle_root: &MerkleNode, leaf: &MerkleNode, proof: &MerkleProof| {
+        let verified: bool = call!(
+            &root,
+            contract.verify_merkle_proof(merkle_root.clone(), leaf.clone(), proof.clone())
+        )
+        .unwrap_json();
+        verified
+    };
+    let sorted_proof = |siblings: Vec<MerkleNode>| MerkleProof {
+        hash: MerkleHash::Keccak256,
+        pairing: Pairing::Sorted,
+        siblings,
+    };
+
+    // ok: the root from OpenZeppelin's `StandardMerkleTree` readme
+    let first = openzeppelin_leaf(
+        "0x1111111111111111111111111111111111111111",
+        "5000000000000000000",
+    );
+    let second = openzeppelin_leaf(
+        "0x2222222222222222222222222222222222222222",
+        "2500000000000000000",
+    );
+    let readme_root = node("0xd4dee0beab2d53f2cc83e567171bd2820e49898130a22622b10ead383e90bd77");
+    assert!(verify(
+        &readme_root,
+        &first,
+        &sorted_proof(vec![second.clone()])
+    ));
+    assert!(verify(&readme_root, &second, &sorted_proof(vec![first])));
+
+    // ok: the leaves and proofs of OpenZeppelin's
+    let data: Value = serde_json::from_str(OPENZEPPELIN_TREE).unwrap();
+    let merkle_root = node(data["root"].as_str().unwrap());
+    let entries = data["entries"].as_array().unwrap();
+    let mut proofs = vec![];
+    for (index, entry) in entries.iter().enumerate() {
+        let leaf = node(entry["leaf"].as_str().unwrap());
+        let value = &entry["value"];
+        let derived = openzeppelin_leaf(value[0].as_str().unwrap(), value[1].as_str().unwrap());
+        assert_eq!(derived, leaf);
+
+        let siblings: Vec<MerkleNode> = entry["proof"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|sibling| node(sibling.as_str().unwrap()))
+            .collect();
+        let proof = sorted_proof(siblings);
+        assert!(verify(&merkle_root, &leaf, &proof), "leaf {}", index);
+        proofs.push((leaf, proof));
+    }
+
+    // fail: a leaf that is not in the tree
+    let (leaf, proof) = proofs.swap_remove(0);
+    let other = openzeppelin_leaf("0x6666666666666666666666666666666666666666", "1");
+    assert!(!verify(&merkle_root, &other, &proof));
+
+    // fail: with the other hash
+    let proof = MerkleProof {
+        hash: MerkleHash::Sha256,
+        ..proof
+    };
+    assert!(!verify(&merkle_root, &leaf, &proof));
+}
+
+#[test]
+fn test_merkle_indexed() {
+    let root = init_simulator(None);
+    let contract = setup_exec(&root);
+
+    let sha256 = MerkleHash::Sha256;
+    let leaves: Vec<MerkleNode> = ["a", "b", "c", "d", "e"]
+        .iter()
+        .map(|leaf| sha256.hash_leaf(leaf.as_bytes()))
+        .collect();
+    let levels = indexed_levels(sha256, leaves.clone());
+    let merkle_root = levels.last().unwrap()[0].clone();
+
+    // ok: the leaves are padded with zeroed nodes up to 8
+    assert_eq!(
+        merkle_root,
+        node("c6cde104e4847b9111f224882d4fb270b5f240f1bd24dda998828dc06303708c")
+    );
+
+    let verify = |merkle_root: &MerkleNode, leaf: &MerkleNode, proof: &MerkleProof| {
+        let verified: bool = call!(
+            &root,
+            contract.verify_merkle_proof(merkle_root.clone(), leaf.clone(), proof.clone())
+        )
+        .unwrap_json();
+        verified
+    };
+    let proof = |index: usize| MerkleProof {
+        hash: sha256,
+        pairing: Pairing::Indexed(index as u32),
+        siblings: levels[..levels.len() - 1]
+            .iter()
+            .enumerate()
+            .map(|(level, nodes)| nodes[(index >> level) ^ 1].clone())
+            .collect(),
+    };
+
+    // ok: every leaf, at its index
+    for (index, leaf) in leaves.iter().enumerate() {
+        assert!(verify(&merkle_root, leaf, &proof(index)), "leaf {}", index);
+    }
+
+    // fail: the leaf at another index
+    let leaf = &leaves[2];
+    let at_other = MerkleProof {
+        pairing: Pairing::Indexed(3),
+        ..proof(2)
+    };
+    assert!(!verify(&merkle_root, leaf, &at_other));
+
+    // fail: an index beyond the height of the tree, which would
+    // otherwise be the same position
+    let beyond = MerkleProof {
+        pairing: Pairing::Indexed(2 + 8),
+        ..proof(2)
+    };
+    assert!(!verify(&merkle_root, leaf, &beyond));
+
+    // fail: the same siblings, but sorted
+    let sorted = MerkleProof {
+        pairing: Pairing::Sorted,
+        ..proof(2)
+    };
+    assert!(!verify(&merkle_root, leaf, &sorted));
+
+    // fail: a tampered sibling
+    let mut tampered = proof(2);
+    tampered.siblings[1].0[0] ^= 1;
+    assert!(!verify(&merkle_root, leaf, &tampered));
+
+    // ok: a single leaf is its own root
+    let single = MerkleProof {
+        hash: sha256,
+        pairing: Pairing::Indexed(0),
+        siblings: vec![],
+    };
+    assert!(verify(&leaves[0], &leaves[0], &single));
+}
@@ -0,0 +1,116 @@
+#![allow(clippy::ref_in_deref)]
+
+use crate::utils::keys::{Curve, KeyPair};
+use crate::utils::setup_exec;
+use crate::utils::sign;
+use near_sdk::json_types::Base64VecU8;
+use near_sdk_sim::{call, init_simulator};
+use nearapps_exec::crypto::nep413::Nonce;
+
+mod utils;
+
+/// The payload as a wallet would serialize it, built by hand
+/// instead of with borsh.
+fn wallet_payload(
+    message: &str,
+    nonce: &[u8; 32],
+    recipient: &str,
+    callback_url: Option<&str>,
+) -> Vec<u8> {
+    let string = |s: &str| {
+        let mut bytes = (s.len() as u32).to_le_bytes().to_vec();
+        bytes.extend(s.as_bytes());
+        bytes
+    };
+    let mut bytes = (2u32.pow(31) + 413).to_le_bytes().to_vec();
+    bytes.extend(string(message));
+    bytes.extend(nonce);
+    bytes.extend(string(recipient));
+    match callback_url {
+        Some(url) => {
+            bytes.push(1);
+            bytes.extend(string(url));
+        }
+        None => bytes.push(0),
+    }
+    bytes
+}
+
+#[test]
+fn test_verify_nep413() {
+    let root = init_simulator(None);
+    let contract = setup_exec(&root);
+
+    let message = "Login with NEAR";
+    let nonce = [42; 32];
+    let recipient = "nearapps.near";
+    let callback_url = Some("https://nearapps.net/login");
+
+    for curve in [Curve::Ed25519, Curve::Secp256k1] {
+        let key = KeyPair::from_seckey(curve, [5; 32]);
+        let payload_hash = sign::sha256(&wallet_payload(message, &nonce, recipient, callback_url));
+        let signature: Base64VecU8 = sign::sign_near(&key, &payload_hash).decode().into();
+
+        let verify =
+            |message: &str, nonce: [u8; 32], recipient: &str, callback_url: Option<&str>| {
+                let res: bool = call!(
+                    &root,
+                    contract.verify_nep413(
+                        key.near_public_key(),
+                        signature.clone(),
+                        message.to_string(),
+                        Nonce(nonce),
+                        recipient.to_string(),
+                        callback_url.map(|s| s.to_string())
+                    )
+                )
+                .unwrap_json();
+                res
+            };
+
+        // ok
+        assert!(verify(message, nonce, recipient, callback_url));
+
+        // fail: any part of the payload is different
+        assert!(!verify("Login with NEAR!", nonce, recipient, callback_url));
+        assert!(!verify(message, [43; 32], recipient, callback_url));
+        assert!(!verify(message, nonce, "evil.near", callback_url));
+        assert!(!verify(message, nonce, recipient, None));
+    }
+
+    // ok: without a callback url
+    let key = KeyPair::from_seckey(Curve::Ed25519, [6; 32]);
+    let payload_hash = sign::sha256(&wallet_payload(message, &nonce, recipient, None));
+    let signature: Base64VecU8 = sign::sign_near(&key, &payload_hash).decode().into();
+    let verified: bool = call!(
+        &root,
+        contract.verify_nep413(
+            key.near_public_key(),
+            signature,
+            message.to_string(),
+            Nonce(nonce),
+            recipient.to_string(),
+            None
+        )
+    )
+    .unwrap_json();
+    assert!(verified);
+}
+
+/// The nonce is base64 on json, as the wallets have it.
+#[test]
+fn test_nep413_nonce() {
+    use near_sdk::serde_json::{from_str, to_string};
+
+    // ok: round-trips as base64
+    let nonce = Nonce([42; 32]);
+    let json = to_string(&nonce).unwrap();
+    assert_eq!(json, format!("\"{}\"", near_sdk::base64::encode([42; 32])));
+    assert_eq!(from_str::<Nonce>(&json).unwrap(), nonce);
+
+    // fail: not 32 bytes
+    let short = format!("\"{}\"", near_sdk::base64::encode([42; 31]));
+    assert!(from_str::<Nonce>(&short).is_err());
+    let long = format!("\"{}\"", near_sdk::base64::encode([42; 33]));
+    assert!(from_str::<Nonce>(&long).is_err());
+}
@@ -0,0 +1,54 @@
+#![allow(clippy::ref_in_deref)]
+
+use crate::utils::{hex, setup_exec};
+use near_sdk_sim::{call, init_simulator};
+use nearapps_exec::crypto::schnorr_secp256k1 as sc;
+use nearapps_exec::hash;
+use std::convert::TryInto;
+
+mod utils;
+
+/// The official test vectors from
+/// <https://github.com/bitcoin/bips/blob/master/bip-0340/test-vectors.csv>.
+const TEST_VECTORS: &str = include_str!("data/bip340_test_vectors.csv");
+
+#[test]
+fn test_schnorr_secp256k1_vectors() {
+    let root = init_simulator(None);
+    let contract = setup_exec(&root);
+
+    for line in TEST_VECTORS.lines().skip(1) {
+        let fields: Vec<&str> = line.split(',').collect();
+        let (index, seckey, pubkey, msg, sign, expected, comment) = (
+            fields[0], fields[1], fields[2], fields[4], fields[5], fields[6], fields[7],
+        );
+
+        // ok: the x-only pubkey derived off-chain matches
+        if !seckey.is_empty() {
+            let seckey = k256::SecretKey::from_bytes(&hex(seckey)).unwrap();
+            let derived: sc::types::XOnlyPubKey = seckey.public_key().into();
+            assert_eq!(derived.0.to_vec(), hex(pubkey), "vector {}", index);
+        }
+
+        let pubkey = sc::types::XOnlyPubKey(hex(pubkey).try_into().unwrap());
+        let sign = sc::types::Sign(hex(sign).try_into().unwrap());
+        let msg = hex(msg);
+
+        let verify: bool = call!(
+            &root,
+            contract.schnorr_secp256k1_verify(pubkey.clone(), sign.clone(), msg.clone())
+        )
+        .unwrap_json();
+        assert_eq!(verify, expected == "TRUE", "vector {}: {}", index, comment);
+
+        // the prehashed verification signs the 32 bytes directly
+        if let Ok(msg_hash) = msg.try_into() {
+            let verify: bool = call!(
+                &root,
+                contract.schnorr_secp256k1_verify_prehashed(pubkey, sign, hash::Sha256(msg_hash))
+            )
+            .unwrap_json();
+            assert_eq!(verify, expected == "TRUE", "vector {}: {}", index, comment);
+        }
+    }
+}
@@ -0,0 +1,100 @@
+#![allow(clippy::ref_in_deref)]
+
+use crate::utils::keys::{Curve, KeyPair};
+use crate::utils::sign;
+use crate::utils::{setup_exec, AssertFailure};
+use near_sdk_sim::{call, init_simulator};
+use nearapps_exec::crypto::threshold::ThresholdVerification;
+use nearapps_exec::error::Error;
+
+mod utils;
+
+#[test]
+fn test_verify_threshold() {
+    let root = init_simulator(None);
+    let contract = setup_exec(&root);
+
+    let keys = vec![
+        KeyPair::from_seckey(Curve::Ed25519, [1; 32]),
+        KeyPair::from_seckey(Curve::Secp256k1, [2; 32]),
+        KeyPair::from_seckey(Curve::Ed25519, [3; 32]),
+    ];
+    let pubkeys: Vec<_> = keys.iter().map(|k| k.near_public_key()).collect();
+    let msg = "approve the action".to_string();
+    let sign = |key: &KeyPair| Some(sign::signed_msg(key, msg.clone()).sign);
+
+    // ok: 2 of 3, from different curves
+    let res: ThresholdVerification = call!(
+        &root,
+        contract.verify_threshold(
+            msg.clone(),
+            vec![sign(&keys[0]), sign(&keys[1]), None],
+            pubkeys.clone(),
+            2
+        )
+    )
+    .unwrap_json();
+    assert!(res.is_met);
+    assert_eq!(res.signers, pubkeys[..2].to_vec());
+
+    // fail: 1 of 3, when 2 are needed
+    let res: ThresholdVerification = call!(
+        &root,
+        contract.verify_threshold(
+            msg.clone(),
+            vec![None, None, sign(&keys[2])],
+            pubkeys.clone(),
+            2
+        )
+    )
+    .unwrap_json();
+    assert!(!res.is_met);
+    assert_eq!(res.signers, vec![pubkeys[2].clone()]);
+
+    // fail: a signature from another key doesn't count
+    let res: ThresholdVerification = call!(
+        &root,
+        contract.verify_threshold(
+            msg.clone(),
+            vec![sign(&keys[0]), None, sign(&keys[0])],
+            pubkeys.clone(),
+            2
+        )
+    )
+    .unwrap_json();
+    assert!(!res.is_met);
+    assert_eq!(res.signers, vec![pubkeys[0].clone()]);
+
+    // ok: a malformed signature counts as not signed
+    let res: ThresholdVerification = call!(
+        &root,
+        contract.verify_threshold(
+            msg.clone(),
+            vec![sign(&keys[0]), Some("1".to_string().into()), None],
+            pubkeys.clone(),
+            1
+        )
+    )
+    .unwrap_json();
+    assert!(res.is_met);
+    assert_eq!(res.signers, vec![pubkeys[0].clone()]);
+
+    // fail: the same signer twice
+    let res = call!(
+        &root,
+        contract.verify_threshold(
+            msg.clone(),
+            vec![sign(&keys[0]), sign(&keys[0])],
+            vec![pubkeys[0].clone(), pubkeys[0].clone()],
+            2
+        )
+    );
+    res.assert_failure(0, Error::DuplicateSigner);
+
+    // fail: more required signers than keys
+    let res = call!(
+        &root,
+        contract.verify_threshold(msg, vec![None, None, None], pubkeys, 4)
+    );
+    res.assert_failure(0, Error::InvalidThreshold);
+}
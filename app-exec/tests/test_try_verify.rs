@@ -0,0 +1,278 @@
+#![allow(clippy::ref_in_deref)]
+
+use crate::utils::keys::{Curve, KeyPair};
+use crate::utils::{args, sign};
+use crate::utils::{setup_exec, AssertFailure};
+use near_sdk_sim::{call, init_simulator};
+use nearapps_exec::crypto::ecdsa_secp256k1::types::VerifyMode;
+use nearapps_exec::crypto::{Bs58EncodedSignature, NearEncodedPubkey, Verification};
+use nearapps_exec::error::Error;
+
+mod utils;
+
+#[test]
+fn test_try_verify() {
+    let root = init_simulator(None);
+    let contract = setup_exec(&root);
+
+    let msg = "This is some message".to_string();
+    let ed_key = KeyPair::from_seckey(Curve::Ed25519, [1; 32]);
+    let secp_key = KeyPair::from_seckey(Curve::Secp256k1, [2; 32]);
+    let ed = sign::signed_msg(&ed_key, msg.clone());
+    let secp = sign::signed_msg(&secp_key, msg.clone());
+
+    let try_verify_in =
+        |sign: &Bs58EncodedSignature, pubkey: &NearEncodedPubkey, mode: Option<VerifyMode>| {
+            let res: Verification = call!(
+                &root,
+                contract.try_verify_msg(sign.clone(), pubkey.clone(), msg.clone(), mode)
+            )
+            .unwrap_json();
+            res
+        };
+    let try_verify = |sign: &Bs58EncodedSignature, pubkey: &NearEncodedPubkey| {
+        try_verify_in(sign, pubkey, Some(VerifyMode::Strict))
+    };
+    let error = |error| Verification {
+        valid: false,
+        error: Some(error),
+    };
+
+    // ok: both curves
+    let valid = Verification {
+        valid: true,
+        error: None,
+    };
+    assert_eq!(try_verify(&ed.sign, &ed.pubkey), valid);
+    assert_eq!(try_verify(&secp.sign, &secp.pubkey), valid);
+
+    // ok: the prehashed version
+    let args = args::verify_hashed_msg(&secp_key, sign::sha256(msg.as_bytes()));
+    let res: Verification = call!(
+        &root,
+        contract.try_verify_hashed_msg(args.sign, args.pubkey, args.msg_hash, None)
+    )
+    .unwrap_json();
+    assert_eq!(res, valid);
+
+    // fail: well-formed, but from another key
+    let other = KeyPair::from_seckey(Curve::Ed25519, [3; 32]);
+    let not_valid = Verification {
+        valid: false,
+        error: None,
+    };
+    assert_eq!(try_verify(&ed.sign, &other.near_public_key()), not_valid);
+
+    // fail: bad base58 ('0' is not in the alphabet)
+    let bad_sign = Bs58EncodedSignature("0".repeat(88));
+    assert_eq!(try_verify(&bad_sign, &ed.pubkey), error(Error::BadBase58));
+    let bad_pubkey = NearEncodedPubkey("ed25519:0".to_string());
+    assert_eq!(try_verify(&ed.sign, &bad_pubkey), error(Error::BadBase58));
+
+    // fail: unknown curve
+    let p256_pubkey = ed.pubkey.0.replace("ed25519:", "p256:");
+    assert_eq!(
+        try_verify(&ed.sign, &NearEncodedPubkey(p256_pubkey)),
+        error(Error::UnknownCurve)
+    );
+
+    // fail: an ed25519 signature for a secp256k1 key, which has the
+    // size of a secp256k1 signature without the recovery id
+    assert_eq!(try_verify(&ed.sign, &secp.pubkey), not_valid);
+
+    // fail: a secp256k1 signature for an ed25519 key
+    assert_eq!(
+        try_verify(&secp.sign, &ed.pubkey),
+        error(Error::WrongSignatureLength)
+    );
+
+    // fail: not a point on the curve, or of the wrong size
+    let off_curve = NearEncodedPubkey(format!(
+        "secp256k1:{}",
+        near_sdk::bs58::encode([1; 64]).into_string()
+    ));
+    assert_eq!(
+        try_verify(&secp.sign, &off_curve),
+        error(Error::InvalidPoint)
+    );
+    let short = NearEncodedPubkey(format!(
+        "ed25519:{}",
+        near_sdk::bs58::encode([1; 31]).into_string()
+    ));
+    assert_eq!(
+        try_verify(&ed.sign, &short),
+        error(Error::WrongPubkeyLength)
+    );
+
+    // fail: secp256k1 signature in the higher-S form, in the strict
+    // mode
+    let high_s = {
+        let mut sign = secp.sign.decode();
+        let s = k256::Scalar::from_bytes_reduced(k256::FieldBytes::from_slice(&sign[32..64]));
+        sign[32..64].copy_from_slice(&(-s).to_bytes());
+        sign[64] ^= 1;
+        Bs58EncodedSignature::encode(&sign)
+    };
+    assert_eq!(
+        try_verify(&high_s, &secp.pubkey),
+        error(Error::NonCanonicalSignature)
+    );
+
+    // ok: but it's accepted by default, as Near does
+    assert_eq!(try_verify_in(&high_s, &secp.pubkey, None), valid);
+    assert_eq!(
+        try_verify_in(&high_s, &secp.pubkey, Some(VerifyMode::Lenient)),
+        valid
+    );
+
+    // fail: ed25519 signature with the higher bits of `s` set
+    let high_bits = {
+        let mut sign = ed.sign.decode();
+        sign[63] |= 0xe0;
+        Bs58EncodedSignature::encode(&sign)
+    };
+    assert_eq!(
+        try_verify(&high_bits, &ed.pubkey),
+        error(Error::NonCanonicalSignature)
+    );
+
+    // ok: verify_msg still doesn't fail on non-canonical signatures
+    let verified: bool = call!(
+        &root,
+        contract.verify_msg(
+            high_s.clone(),
+            secp.pubkey.clone(),
+            msg.clone(),
+            Some(VerifyMode::Strict)
+        )
+    )
+    .unwrap_json();
+    assert!(!verified);
+    let verified: bool = call!(
+        &root,
+        contract.verify_msg(high_s, secp.pubkey, msg.clone(), None)
+    )
+    .unwrap_json();
+    assert!(verified);
+
+    // fail: but verify_msg fails on other malformed inputs, with the
+    // same reasons
+    let res = call!(&root, contract.verify_msg(bad_sign, ed.pubkey, msg, None));
+    res.assert_failure(0, Error::BadBase58);
+}
+
+#[test]
+fn test_verify_encodings() {
+    use crate::utils::keys;
+
+    let root = init_simulator(None);
+    let contract = setup_exec(&root);
+
+    let msg = "This is some message".to_string();
+    let ed_key = KeyPair::from_seckey(Curve::Ed25519, [1; 32]);
+    let secp_key = KeyPair::from_seckey(Curve::Secp256k1, [2; 32]);
+    let ed = sign::signed_msg(&ed_key, msg.clone());
+    let secp = sign::signed_msg(&secp_key, msg.clone());
+
+    let try_verify = |sign: String, pubkey: String| {
+        let res: Verification = call!(
+            &root,
+            contract.try_verify_msg(
+                Bs58EncodedSignature(sign),
+                NearEncodedPubkey(pubkey),
+                msg.clone(),
+                None
+            )
+        )
+        .unwrap_json();
+        res
+    };
+    let valid = Verification {
+        valid: true,
+        error: None,
+    };
+    let error = |error| Verification {
+        valid: false,
+        error: Some(error),
+    };
+    let to_hex = |bytes: &[u8]| -> String { bytes.iter().map(|b| format!("{:02x}", b)).collect() };
+
+    let ed_sign = ed.sign.decode();
+    let ed_pubkey = near_sdk::bs58::decode(ed.pubkey.0.trim_start_matches("ed25519:"))
+        .into_vec()
+        .unwrap();
+
+    // ok: tagged encodings, for both the signature and the pubkey
+    for (sign, pubkey) in [
+        (
+            format!("hex:{}", to_hex(&ed_sign)),
+            format!("ed25519:hex:{}", to_hex(&ed_pubkey)),
+        ),
+        (
+            format!("base64:{}", near_sdk::base64::encode(&ed_sign)),
+            format!("ed25519:base64:{}", near_sdk::base64::encode(&ed_pubkey)),
+        ),
+        (
+            format!("base58:{}", ed.sign.0),
+            format!(
+                "base58:{}",
+                near_sdk::bs58::encode(&ed_pubkey).into_string()
+            ),
+        ),
+    ] {
+        assert_eq!(try_verify(sign, pubkey), valid);
+    }
+
+    // ok: `0x`-prefixed hex, where a 32-byte key is detected as ed25519
+    assert_eq!(
+        try_verify(
+            format!("0x{}", to_hex(&ed_sign)),
+            format!("0x{}", to_hex(&ed_pubkey))
+        ),
+        valid
+    );
+
+    // ok: secp256k1 keys in the SEC1 forms, without the curve prefix
+    let compressed = keys::secp256k1_pubkey_compressed(secp_key.secp256k1_seckey());
+    let uncompressed = keys::secp256k1_pubkey_uncompressed(secp_key.secp256k1_seckey());
+    assert_eq!(compressed.0.len(), 33);
+    assert_eq!(uncompressed.0.len(), 65);
+    for pubkey in [to_hex(&compressed.0), to_hex(&uncompressed.0)] {
+        assert_eq!(
+            try_verify(secp.sign.0.clone(), format!("0x{}", pubkey)),
+            valid
+        );
+    }
+
+    // ok: secp256k1 signature without the recovery id
+    let secp_sign = secp.sign.decode();
+    assert_eq!(
+        try_verify(
+            format!("0x{}", to_hex(&secp_sign[..64])),
+            secp.pubkey.0.clone()
+        ),
+        valid
+    );
+
+    // fail: malformed hex and base64
+    assert_eq!(
+        try_verify(format!("0x{}", "zz".repeat(64)), ed.pubkey.0.clone()),
+        error(Error::BadHex)
+    );
+    assert_eq!(
+        try_verify(format!("0x{}", &to_hex(&ed_sign)[1..]), ed.pubkey.0.clone()),
+        error(Error::BadHex)
+    );
+    assert_eq!(
+        try_verify("base64:!!!!".to_string(), ed.pubkey.0),
+        error(Error::BadBase64)
+    );
+
+    // fail: a 65-byte key with the wrong SEC1 header
+    let mut bad_header = uncompressed.0;
+    bad_header[0] = 0x05;
+    assert_eq!(
+        try_verify(secp.sign.0, format!("0x{}", to_hex(&bad_header))),
+        error(Error::InvalidPoint)
+    );
+}
@@ -0,0 +1,298 @@
+#![allow(clippy::ref_in_deref)]
+
+use crate::utils::args::{self, VerifyTaggedMsgArgs};
+use crate::utils::keys::{Curve, KeyPair};
+use crate::utils::sign;
+use crate::utils::{hex, setup_exec, AssertFailure};
+use near_sdk_sim::{call, init_simulator};
+use nearapps_exec::crypto::algorithm::Algorithm;
+use nearapps_exec::crypto::eddsa_ed25519::types::{PubKey, SecKey, Sign};
+use nearapps_exec::crypto::{Bs58EncodedSignature, Verification};
+use nearapps_exec::error::Error;
+use nearapps_exec::Executor;
+use std::convert::TryInto;
+
+mod utils;
+
+/// The `Ed25519ctx` test vectors of
+/// [rfc8032](https://datatracker.ietf.org/doc/html/rfc8032#section-7.2),
+/// as `(seckey, pubkey, msg, context, sign)`.
+const ED25519CTX_VECTORS: &[(&str, &str, &str, &str, &str)] = &[
+    (
+        "0305334e381af78f141cb666f6199f57bc3495335a256a95bd2a55bf546663f6",
+        "dfc9425e4f968f7f0c29f0259cf5f9aed6851c2bb4ad8bfb860cfee0ab248292",
+        "f726936d19c800494e3fdaff20b276a8",
+        "foo",
+        "55a4cc2f70a54e04288c5f4cd1e45a7bb520b36292911876cada7323198dd87a8b36950b95130022907a7fb7c4e9b2d5f6cca685a587b4b21f4b888e4e7edb0d",
+    ),
+    (
+        "0305334e381af78f141cb666f6199f57bc3495335a256a95bd2a55bf546663f6",
+        "dfc9425e4f968f7f0c29f0259cf5f9aed6851c2bb4ad8bfb860cfee0ab248292",
+        "f726936d19c800494e3fdaff20b276a8",
+        "bar",
+        "fc60d5872fc46b3aa69f8b5b4351d5808f92bcc044606db097abab6dbcb1aee3216c48e8b3b66431b5b186d1d28f8ee15a5ca2df6668346291c2043d4eb3e90d",
+    ),
+    (
+        "0305334e381af78f141cb666f6199f57bc3495335a256a95bd2a55bf546663f6",
+        "dfc9425e4f968f7f0c29f0259cf5f9aed6851c2bb4ad8bfb860cfee0ab248292",
+        "508e9e6882b979fea900f62adceaca35",
+        "foo",
+        "8b70c1cc8310e1de20ac53ce28ae6e7207f33c3295e03bb5c0732a1d20dc64908922a8b052cf99b7c4fe107a5abb5b2c4085ae75890d02df26269d8945f84b0b",
+    ),
+    (
+        "ab9c2853ce297ddab85c993b3ae14bcad39b2c682beabc27d6d4eb20711d6560",
+        "0f1d1274943b91415889152e893d80e93275a1fc0b65fd71b4b0dda10ad7d772",
+        "f726936d19c800494e3fdaff20b276a8",
+        "foo",
+        "21655b5f1aa965996b3f97b3c849eafba922a0a62992f73b3d1b73106a84ad85e9b86a7b6005ea868337ff2d20a7f5fbd4cd10b0be49a68da2b2e0dc0ad8960f",
+    ),
+];
+
+#[test]
+fn test_ed25519ctx_vectors() {
+    for (seckey, pubkey, msg, context, sign) in ED25519CTX_VECTORS {
+        let seckey = SecKey(hex(seckey).try_into().unwrap());
+        let pubkey = PubKey(hex(pubkey).try_into().unwrap());
+        let msg = hex(msg);
+        let sign = Sign(hex(sign).try_into().unwrap());
+
+        // ok: the same signature as the rfc's
+        assert_eq!(
+            sign::eddsa_ed25519_sign_ctx(seckey.clone(), &msg, context),
+            sign.clone()
+        );
+        assert!(Executor::eddsa_ed25519_verify_ctx(
+            pubkey.clone(),
+            sign.clone(),
+            &msg,
+            context.as_bytes()
+        ));
+
+        // fail: with another context, or with none
+        assert!(!Executor::eddsa_ed25519_verify_ctx(
+            pubkey.clone(),
+            sign.clone(),
+            &msg,
+            b"baz"
+        ));
+        assert!(!Executor::eddsa_ed25519_verify_ctx(
+            pubkey.clone(),
+            sign.clone(),
+            &msg,
+            b""
+        ));
+        assert!(!Executor::eddsa_ed25519_verify(
+            pubkey.clone(),
+            sign.clone(),
+            &msg
+        ));
+
+        // fail: a context that is too long
+        let long = [b'a'; 256];
+        assert!(!Executor::eddsa_ed25519_verify_ctx(
+            pubkey.clone(),
+            sign.clone(),
+            &msg,
+            &long
+        ));
+        assert_eq!(
+            nearapps_exec::crypto::eddsa_ed25519::verify::dom2(&long),
+            Err(Error::BadContext)
+        );
+
+        // fail: `A` or `R` of a small order, or not canonically encoded
+        let mut identity = [0; 32];
+        identity[0] = 1;
+        let mut non_canonical = [0xff; 32];
+        non_canonical[0] = 0xee;
+        non_canonical[31] = 0x7f;
+        for point in [identity, non_canonical] {
+            assert!(!Executor::eddsa_ed25519_verify_ctx(
+                PubKey(point),
+                sign.clone(),
+                &msg,
+                context.as_bytes()
+            ));
+            let mut small_r = sign.clone();
+            small_r.0[..32].copy_from_slice(&point);
+            assert!(!Executor::eddsa_ed25519_verify_ctx(
+                pubkey.clone(),
+                small_r,
+                &msg,
+                context.as_bytes()
+            ));
+        }
+    }
+}
+
+#[test]
+fn test_verify_tagged() {
+    let root = init_simulator(None);
+    let contract = setup_exec(&root);
+
+    let msg = "This is some message".to_string();
+    let ed_key = KeyPair::from_seckey(Curve::Ed25519, [1; 32]);
+    let secp_key = KeyPair::from_seckey(Curve::Secp256k1, [2; 32]);
+
+    let verify = |args: &VerifyTaggedMsgArgs| {
+        let res: bool = call!(
+            &root,
+            contract.verify_tagged_msg(
+                args.algorithm.clone(),
+                args.sign.clone(),
+                args.pubkey.clone(),
+                args.msg.clone()
+            )
+        )
+        .unwrap_json();
+        res
+    };
+    let try_verify = |args: &VerifyTaggedMsgArgs| {
+        let res: Verification = call!(
+            &root,
+            contract.try_verify_tagged_msg(
+                args.algorithm.clone(),
+                args.sign.clone(),
+                args.pubkey.clone(),
+                args.msg.clone()
+            )
+        )
+        .unwrap_json();
+        res
+    };
+    let error = |error| Verification {
+        valid: false,
+        error: Some(error),
+    };
+
+    let ed25519 = Algorithm::Ed25519;
+    let ed25519ph = Algorithm::Ed25519ph {
+        context: Some("app".to_string()),
+    };
+    let ed25519ctx = Algorithm::Ed25519ctx {
+        context: "app".to_string(),
+    };
+
+    // ok: every algorithm
+    let algorithms = vec![
+        (&ed_key, ed25519.clone()),
+        (&ed_key, ed25519ph.clone()),
+        (&ed_key, Algorithm::Ed25519ph { context: None }),
+        (&ed_key, ed25519ctx.clone()),
+        (&secp_key, Algorithm::EcdsaSecp256k1),
+        (&secp_key, Algorithm::EcdsaSecp256k1Recoverable),
+    ];
+    for (key, algorithm) in algorithms {
+        let args = args::verify_tagged_msg(key, algorithm.clone(), msg.clone());
+        assert!(verify(&args), "{:?}", algorithm);
+    }
+
+    // ok: plain ed25519 is the same as Near's
+    let args = args::verify_tagged_msg(&ed_key, ed25519.clone(), msg.clone());
+    assert_eq!(args.sign, sign::signed_msg(&ed_key, msg.clone()).sign);
+
+    // ok: with the msg already hashed
+    let ctx_args = args::verify_tagged_msg(&ed_key, ed25519ctx.clone(), msg.clone());
+    let res: Verification = call!(
+        &root,
+        contract.try_verify_tagged_hashed_msg(
+            ed25519ctx.clone(),
+            ctx_args.sign.clone(),
+            ctx_args.pubkey.clone(),
+            sign::sha256(msg.as_bytes())
+        )
+    )
+    .unwrap_json();
+    assert_eq!(
+        res,
+        Verification {
+            valid: true,
+            error: None
+        }
+    );
+
+    // fail: the ed25519 algorithms are not mistaken for each other
+    let ph_args = args::verify_tagged_msg(&ed_key, ed25519ph.clone(), msg.clone());
+    for (sign, algorithm) in [
+        (&args.sign, &ed25519ph),
+        (&args.sign, &ed25519ctx),
+        (&ph_args.sign, &ed25519),
+        (&ph_args.sign, &ed25519ctx),
+        (&ctx_args.sign, &ed25519),
+        (&ctx_args.sign, &ed25519ph),
+    ] {
+        let args = VerifyTaggedMsgArgs {
+            algorithm: algorithm.clone(),
+            sign: sign.clone(),
+            ..args.clone()
+        };
+        assert!(!verify(&args), "{:?}", algorithm);
+    }
+
+    // fail: with another context
+    let other_context = VerifyTaggedMsgArgs {
+        algorithm: Algorithm::Ed25519ctx {
+            context: "other".to_string(),
+        },
+        ..ctx_args.clone()
+    };
+    assert!(!verify(&other_context));
+    let other_context = VerifyTaggedMsgArgs {
+        algorithm: Algorithm::Ed25519ph { context: None },
+        ..ph_args
+    };
+    assert!(!verify(&other_context));
+
+    // fail: the recovery id recovers another key
+    let recoverable = args::verify_tagged_msg(&secp_key, Algorithm::EcdsaSecp256k1Recoverable, msg);
+    let mut sign = recoverable.sign.decode();
+    sign[64] ^= 1;
+    let flipped = VerifyTaggedMsgArgs {
+        sign: Bs58EncodedSignature::encode(&sign),
+        ..recoverable.clone()
+    };
+    assert!(!verify(&flipped));
+
+    // fail: the recoverable signature without the recovery id
+    let without_recovery_id = VerifyTaggedMsgArgs {
+        sign: Bs58EncodedSignature::encode(&sign[..64]),
+        ..recoverable
+    };
+    assert_eq!(
+        try_verify(&without_recovery_id),
+        error(Error::WrongSignatureLength)
+    );
+
+    // fail: the algorithm is for another curve
+    let wrong_curve = VerifyTaggedMsgArgs {
+        algorithm: Algorithm::EcdsaSecp256k1,
+        ..args
+    };
+    assert_eq!(try_verify(&wrong_curve), error(Error::WrongAlgorithm));
+    let res = call!(
+        &root,
+        contract.verify_tagged_msg(
+            wrong_curve.algorithm,
+            wrong_curve.sign,
+            wrong_curve.pubkey,
+            wrong_curve.msg
+        )
+    );
+    res.assert_failure(0, Error::WrongAlgorithm);
+
+    // fail: Ed25519ctx requires a context
+    let empty_context = VerifyTaggedMsgArgs {
+        algorithm: Algorithm::Ed25519ctx {
+            context: String::new(),
+        },
+        ..ctx_args.clone()
+    };
+    assert_eq!(try_verify(&empty_context), error(Error::BadContext));
+    let long_context = VerifyTaggedMsgArgs {
+        algorithm: Algorithm::Ed25519ph {
+            context: Some("a".repeat(256)),
+        },
+        ..ctx_args
+    };
+    assert_eq!(try_verify(&long_context), error(Error::BadContext));
+}
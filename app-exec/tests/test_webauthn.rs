@@ -0,0 +1,162 @@
+#![allow(clippy::ref_in_deref)]
+
+use crate::utils::{hex, keys, passkey, setup_exec, AssertFailure};
+use near_sdk_sim::{call, init_simulator};
+use nearapps_exec::crypto::{ecdsa_secp256r1 as r1, webauthn};
+use nearapps_exec::error::Error;
+use std::convert::TryInto;
+
+mod utils;
+
+const RP_ID: &str = "nearapps.net";
+const ORIGIN: &str = "https://nearapps.net";
+
+#[test]
+fn test_webauthn() {
+    use webauthn::types::{Assertion, FLAG_USER_PRESENT, FLAG_USER_VERIFIED};
+
+    let root = init_simulator(None);
+    let contract = setup_exec(&root);
+
+    let challenge: Vec<u8> = (0..32).collect();
+
+    // ok: an assertion made by an independent implementation, with
+    // more fields on the client data and a higher-S signature
+    {
+        let pubkey = r1::types::PubKeyUncompressed(
+            hex("040217e617f0b6443928278f96999e69a23a4f2c152bdf6d6cdf66e5b80282d4ed194a7debcb97712d2dda3ca85aa8765a56f45fc758599652f2897c65306e5794")
+                .try_into()
+                .unwrap(),
+        );
+        let assertion = Assertion {
+            authenticator_data: hex(
+                "cf3881511e5186900cc8f0b1d5adcf5dc73d0349965e1451482e1854c77c9bda0500000001",
+            ),
+            client_data_json: r#"{"type":"webauthn.get","challenge":"AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8","origin":"https://nearapps.net","crossOrigin":false}"#.to_string(),
+            signature: hex("304502201c9e7e74d6a7fc52d5e78fcc41a51e42314697846057997ea32ef63c9b38ca79022100ab13316b9e57b7ba0dc6cf5192a9a3207f16f06067f60b0e48c0ecc2d6bcb219"),
+        };
+        let verify: bool = call!(
+            &root,
+            contract.webauthn_verify(
+                pubkey,
+                assertion,
+                RP_ID.to_string(),
+                ORIGIN.to_string(),
+                challenge.clone(),
+                true
+            )
+        )
+        .unwrap_json();
+        assert!(verify);
+    }
+
+    let seckey = r1::types::SecKey([7; 32]);
+    let pubkey = keys::secp256r1_pubkey_uncompressed(seckey.clone());
+    let assertion_from = |origin: &str, challenge: &[u8], flags: u8| {
+        passkey::assertion(seckey.clone(), RP_ID, origin, challenge, flags)
+    };
+    let assertion = |rp_id: &str, challenge: &[u8], flags: u8| {
+        passkey::assertion(seckey.clone(), rp_id, ORIGIN, challenge, flags)
+    };
+    let verify = |assertion: Assertion, challenge: &[u8], require_user_verification: bool| {
+        let res: bool = call!(
+            &root,
+            contract.webauthn_verify(
+                pubkey.clone(),
+                assertion,
+                RP_ID.to_string(),
+                ORIGIN.to_string(),
+                challenge.to_vec(),
+                require_user_verification
+            )
+        )
+        .unwrap_json();
+        res
+    };
+
+    // ok: user present, and verified when required
+    assert!(verify(
+        assertion(RP_ID, &challenge, FLAG_USER_PRESENT),
+        &challenge,
+        false
+    ));
+    assert!(verify(
+        assertion(RP_ID, &challenge, FLAG_USER_PRESENT | FLAG_USER_VERIFIED),
+        &challenge,
+        true
+    ));
+
+    // fail: another relying party
+    assert!(!verify(
+        assertion("other.net", &challenge, FLAG_USER_PRESENT),
+        &challenge,
+        false
+    ));
+
+    // fail: the user was not present
+    assert!(!verify(
+        assertion(RP_ID, &challenge, FLAG_USER_VERIFIED),
+        &challenge,
+        false
+    ));
+
+    // fail: the user was not verified
+    assert!(!verify(
+        assertion(RP_ID, &challenge, FLAG_USER_PRESENT),
+        &challenge,
+        true
+    ));
+
+    // fail: another challenge
+    assert!(!verify(
+        assertion(RP_ID, b"another challenge", FLAG_USER_PRESENT),
+        &challenge,
+        false
+    ));
+
+    // fail: another origin, even if it shares the relying party
+    assert!(!verify(
+        assertion_from("https://evil.nearapps.net", &challenge, FLAG_USER_PRESENT),
+        &challenge,
+        false
+    ));
+
+    // fail: the client data was changed
+    let mut changed = assertion(RP_ID, &challenge, FLAG_USER_PRESENT);
+    changed.client_data_json = changed
+        .client_data_json
+        .replace('}', r#","crossOrigin":true}"#);
+    assert!(!verify(changed, &challenge, false));
+
+    // fail: the signature is not DER encoded
+    let mut bad_sign = assertion(RP_ID, &challenge, FLAG_USER_PRESENT);
+    bad_sign.signature[0] = 0x31;
+    let res = call!(
+        &root,
+        contract.webauthn_verify(
+            pubkey.clone(),
+            bad_sign,
+            RP_ID.to_string(),
+            ORIGIN.to_string(),
+            challenge.clone(),
+            false
+        )
+    );
+    res.assert_failure(0, Error::BadDerSignature);
+
+    // fail: the authenticator data is too short
+    let mut short = assertion(RP_ID, &challenge, FLAG_USER_PRESENT);
+    short.authenticator_data.truncate(33);
+    let res = call!(
+        &root,
+        contract.webauthn_verify(
+            pubkey,
+            short,
+            RP_ID.to_string(),
+            ORIGIN.to_string(),
+            challenge,
+            false
+        )
+    );
+    res.assert_failure(0, Error::BadAuthenticatorData);
+}
@@ -0,0 +1,87 @@
+//! Signed arguments for the verification calls, as the cli would
+//! create them.
+
+use super::keys::{self, Curve, KeyPair};
+use super::sign::{self, sha256};
+use nearapps_exec::crypto::algorithm::Algorithm;
+use nearapps_exec::crypto::envelope::Envelope;
+use nearapps_exec::crypto::{eth, Bs58EncodedSignature, NearEncodedPubkey};
+use nearapps_exec::hash;
+
+/// Arguments for `Executor::verify_hashed_msg()`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct VerifyHashedMsgArgs {
+    pub sign: Bs58EncodedSignature,
+    pub pubkey: NearEncodedPubkey,
+    pub msg_hash: hash::Sha256,
+}
+
+/// Arguments for `Executor::verify_tagged_msg()`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct VerifyTaggedMsgArgs {
+    pub algorithm: Algorithm,
+    pub sign: Bs58EncodedSignature,
+    pub pubkey: NearEncodedPubkey,
+    pub msg: String,
+}
+
+/// Arguments for `Executor::verify_by_eth_address()`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct VerifyByEthAddressArgs {
+    pub address: eth::types::ChecksumAddress,
+    pub sign: Bs58EncodedSignature,
+    pub msg: String,
+}
+
+/// Arguments for `Executor::verify_envelope()`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct VerifyEnvelopeArgs {
+    pub envelope: Envelope,
+    pub sign: Bs58EncodedSignature,
+    pub pubkey: NearEncodedPubkey,
+    pub domain: String,
+}
+
+pub fn verify_hashed_msg(key: &KeyPair, msg_hash: hash::Sha256) -> VerifyHashedMsgArgs {
+    VerifyHashedMsgArgs {
+        sign: sign::sign_near(key, &msg_hash),
+        pubkey: key.near_public_key(),
+        msg_hash,
+    }
+}
+
+/// Signs the `sha256` of the `msg` with the `algorithm`.
+///
+/// Panics if the `algorithm` is not for the curve of the `key`.
+pub fn verify_tagged_msg(key: &KeyPair, algorithm: Algorithm, msg: String) -> VerifyTaggedMsgArgs {
+    let msg_hash = sha256(msg.as_bytes());
+    VerifyTaggedMsgArgs {
+        sign: sign::sign_tagged(key, &algorithm, &msg_hash),
+        pubkey: key.near_public_key(),
+        algorithm,
+        msg,
+    }
+}
+
+/// Signs the `msg` with `personal_sign`.
+///
+/// Panics if the `key` is not a secp256k1 key.
+pub fn verify_by_eth_address(key: &KeyPair, msg: String) -> VerifyByEthAddressArgs {
+    assert_eq!(key.curve, Curve::Secp256k1);
+    let sign = sign::eth_personal_sign(key.secp256k1_seckey(), &msg);
+    VerifyByEthAddressArgs {
+        address: keys::secp256k1_eth_address(key.secp256k1_seckey()),
+        sign: Bs58EncodedSignature::encode(&sign.0),
+        msg,
+    }
+}
+
+/// Signs the `envelope`, which is checked against its own `domain`.
+pub fn verify_envelope(key: &KeyPair, envelope: Envelope) -> VerifyEnvelopeArgs {
+    VerifyEnvelopeArgs {
+        sign: sign::sign_near(key, &envelope.hash()),
+        pubkey: key.near_public_key(),
+        domain: envelope.domain.clone(),
+        envelope,
+    }
+}
@@ -0,0 +1,82 @@
+//! Keys for the tests, from fixed secret keys.
+//!
+//! The executor doesn't depend on the cli, so the tests derive their
+//! own public keys.
+
+use nearapps_exec::crypto::{
+    ecdsa_secp256k1 as ec, ecdsa_secp256r1 as r1, eddsa_ed25519 as ed, eth, NearEncodedPubkey,
+};
+use std::convert::TryInto;
+
+/// The curves that Near accounts can have keys on.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Curve {
+    Ed25519,
+    Secp256k1,
+}
+
+/// A secret key, and the curve that it's on.
+#[derive(Clone, PartialEq, Debug)]
+pub struct KeyPair {
+    pub curve: Curve,
+    pub seckey: [u8; 32],
+}
+
+impl KeyPair {
+    /// Panics if `seckey` is not a valid secp256k1 secret key.
+    pub fn from_seckey(curve: Curve, seckey: [u8; 32]) -> Self {
+        if curve == Curve::Secp256k1 {
+            k256::SecretKey::from_bytes(&seckey).unwrap();
+        }
+        Self { curve, seckey }
+    }
+
+    /// The Near encoding of the public key.
+    pub fn near_public_key(&self) -> NearEncodedPubkey {
+        match self.curve {
+            Curve::Ed25519 => {
+                let pubkey: near_sdk::PublicKey =
+                    ed25519_pubkey(self.ed25519_seckey()).try_into().unwrap();
+                String::from(&pubkey).into()
+            }
+            Curve::Secp256k1 => {
+                let pubkey = k256::SecretKey::from_bytes(&self.seckey)
+                    .unwrap()
+                    .public_key();
+                let pubkey: ec::types::PubKeyUncompressedNoHeader = pubkey.into();
+                pubkey.into()
+            }
+        }
+    }
+
+    pub fn ed25519_seckey(&self) -> ed::types::SecKey {
+        ed::types::SecKey(self.seckey)
+    }
+
+    pub fn secp256k1_seckey(&self) -> ec::types::SecKey {
+        ec::types::SecKey(self.seckey)
+    }
+}
+
+pub fn ed25519_pubkey(seckey: ed::types::SecKey) -> ed::types::PubKey {
+    (&seckey).into()
+}
+
+pub fn secp256k1_pubkey_compressed(seckey: ec::types::SecKey) -> ec::types::PubKeyCompressed {
+    let seckey = k256::SecretKey::from_bytes(&seckey.0).unwrap();
+    seckey.public_key().into()
+}
+
+pub fn secp256k1_pubkey_uncompressed(seckey: ec::types::SecKey) -> ec::types::PubKeyUncompressed {
+    let seckey = k256::SecretKey::from_bytes(&seckey.0).unwrap();
+    seckey.public_key().into()
+}
+
+pub fn secp256k1_eth_address(seckey: ec::types::SecKey) -> eth::types::ChecksumAddress {
+    eth::types::Address::from(secp256k1_pubkey_uncompressed(seckey)).into()
+}
+
+pub fn secp256r1_pubkey_uncompressed(seckey: r1::types::SecKey) -> r1::types::PubKeyUncompressed {
+    let seckey = p256::SecretKey::from_bytes(&seckey.0).unwrap();
+    seckey.public_key().into()
+}
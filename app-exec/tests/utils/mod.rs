@@ -8,6 +8,10 @@ use nearapps_counter::CounterContract;
 use nearapps_exec::ExecutorContract;
 
 pub mod _secp256k1;
+pub mod args;
+pub mod keys;
+pub mod passkey;
+pub mod sign;
 
 near_sdk_sim::lazy_static_include::lazy_static_include_bytes! {
     EXEC_WASM_BYTES => "../res/nearapps_exec.wasm",
@@ -48,11 +52,17 @@ impl AssertFailure for ExecutionResult {
 }
 
 pub fn setup_exec(root: &UserAccount) -> Contract {
+    setup_exec_at(root, root, "executor")
+}
+
+/// Deploys the executor on the `contract_id`, which must be a
+/// sub-account of the `signer`, and owned by `root`.
+pub fn setup_exec_at(root: &UserAccount, signer: &UserAccount, contract_id: &str) -> Contract {
     let contract = deploy!(
         contract: ExecutorContract,
-        contract_id: "executor".to_string(),
+        contract_id: contract_id.to_string(),
         bytes: &EXEC_WASM_BYTES,
-        signer_account: root,
+        signer_account: signer,
         deposit: 200 * YOTTA,
         init_method: new(root.account_id())
     );
@@ -73,3 +83,12 @@ pub fn setup_counter(root: &UserAccount) -> ContractAccount<CounterContract> {
 fn user(id: u32) -> AccountId {
     format!("user{}", id).parse().unwrap()
 }
+
+/// Decodes a hex string, with an optional `0x` prefix.
+pub fn hex(s: &str) -> Vec<u8> {
+    let s = s.trim_start_matches("0x");
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+        .collect()
+}
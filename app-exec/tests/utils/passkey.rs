@@ -0,0 +1,43 @@
+//! A software passkey, to create WebAuthn assertions for the tests.
+
+use super::sign::{ecdsa_secp256r1_sign, sha256};
+use nearapps_exec::crypto::{der, ecdsa_secp256r1 as r1, webauthn};
+
+/// Creates a WebAuthn assertion of the `challenge`, as an
+/// authenticator with the passkey `seckey` would, for
+/// `Executor::webauthn_verify()`.
+///
+/// `flags` are the authenticator data flags, eg.
+/// [`webauthn::types::FLAG_USER_PRESENT`].
+pub fn assertion(
+    seckey: r1::types::SecKey,
+    rp_id: &str,
+    origin: &str,
+    challenge: &[u8],
+    flags: u8,
+) -> webauthn::types::Assertion {
+    let mut authenticator_data = sha256(rp_id.as_bytes()).0.to_vec();
+    authenticator_data.push(flags);
+    // the signature counter
+    authenticator_data.extend(&[0; 4]);
+
+    let client_data = webauthn::types::ClientData {
+        type_: webauthn::types::CLIENT_DATA_TYPE_GET.to_string(),
+        challenge: {
+            use near_sdk::base64;
+            base64::encode_config(challenge, base64::URL_SAFE_NO_PAD)
+        },
+        origin: origin.to_string(),
+    };
+    let client_data_json = near_sdk::serde_json::to_string(&client_data).unwrap();
+
+    let mut signed = authenticator_data.clone();
+    signed.extend(&sha256(client_data_json.as_bytes()).0);
+    let sign = ecdsa_secp256r1_sign(seckey, &signed);
+
+    webauthn::types::Assertion {
+        authenticator_data,
+        client_data_json,
+        signature: der::encode_ecdsa_signature(&sign.0),
+    }
+}
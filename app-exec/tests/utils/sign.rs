@@ -0,0 +1,201 @@
+//! Signing for the tests, as the cli and wallets would sign.
+//!
+//! The signatures are deterministic, so the tests are reproducible.
+
+use super::keys::{Curve, KeyPair};
+use nearapps_exec::crypto::algorithm::Algorithm;
+use nearapps_exec::crypto::{
+    ecdsa_secp256k1 as ec, ecdsa_secp256r1 as r1, eddsa_ed25519 as ed, Bs58EncodedSignature,
+    SignedMsg,
+};
+use nearapps_exec::hash;
+
+/// The `sha256` of `msg_bytes`, computed off-chain.
+pub fn sha256(msg_bytes: &[u8]) -> hash::Sha256 {
+    use sha2::Digest;
+    let mut res = [0; 32];
+    res.copy_from_slice(&sha2::Sha256::digest(msg_bytes));
+    hash::Sha256(res)
+}
+
+/// Signs the `sha256` of `msg_bytes`, in the lower-S form.
+pub fn ecdsa_secp256k1_sign(seckey: ec::types::SecKey, msg_bytes: &[u8]) -> ec::types::SignCompact {
+    let signing_key = k256::ecdsa::SigningKey::from_bytes(&seckey.0).unwrap();
+    let mut sign: k256::ecdsa::Signature = {
+        use k256::ecdsa::signature::DigestSigner;
+        use sha2::Digest;
+        signing_key.sign_digest(sha2::Sha256::new().chain(msg_bytes))
+    };
+    sign.normalize_s().unwrap();
+    sign.into()
+}
+
+/// Signs the `sha256` of `msg_bytes`, followed by the recovery id.
+pub fn ecdsa_secp256k1_sign_recoverable(
+    seckey: ec::types::SecKey,
+    msg_bytes: &[u8],
+) -> ec::types::SignRecoverable {
+    let signing_key = k256::ecdsa::SigningKey::from_bytes(&seckey.0).unwrap();
+    let sign: k256::ecdsa::recoverable::Signature = {
+        use k256::ecdsa::signature::DigestSigner;
+        use sha2::Digest;
+        signing_key.sign_digest(sha2::Sha256::new().chain(msg_bytes))
+    };
+    sign.into()
+}
+
+/// Signs the `sha256` of `msg_bytes`, without normalizing the
+/// signature, as WebAuthn authenticators.
+pub fn ecdsa_secp256r1_sign(seckey: r1::types::SecKey, msg_bytes: &[u8]) -> r1::types::SignCompact {
+    let signing_key = p256::ecdsa::SigningKey::from_bytes(&seckey.0).unwrap();
+    let sign: p256::ecdsa::Signature = {
+        use p256::ecdsa::signature::DigestSigner;
+        use sha2::Digest;
+        signing_key.sign_digest(sha2::Sha256::new().chain(msg_bytes))
+    };
+    sign.into()
+}
+
+fn ed25519_keypair(seckey: ed::types::SecKey) -> ed25519_dalek::Keypair {
+    let secret = ed25519_dalek::SecretKey::from_bytes(&seckey.0).unwrap();
+    let public: ed25519_dalek::PublicKey = (&secret).into();
+    ed25519_dalek::Keypair { secret, public }
+}
+
+pub fn eddsa_ed25519_sign(seckey: ed::types::SecKey, msg_bytes: &[u8]) -> ed::types::Sign {
+    use ed25519_dalek::Signer;
+    ed25519_keypair(seckey).sign(msg_bytes).into()
+}
+
+/// Signs the `msg_hash` with the `Ed25519ph` algorithm.
+pub fn eddsa_ed25519_sign_prehashed(
+    seckey: ed::types::SecKey,
+    msg_hash: hash::Sha512,
+    context: Option<&str>,
+) -> ed::types::SignPrehashed {
+    let context = context.map(|s| s.as_bytes());
+    ed25519_keypair(seckey)
+        .sign_prehashed(msg_hash, context)
+        .unwrap()
+        .into()
+}
+
+/// Signs the `msg_bytes` with the `Ed25519ctx` algorithm.
+pub fn eddsa_ed25519_sign_ctx(
+    seckey: ed::types::SecKey,
+    msg_bytes: &[u8],
+    context: &str,
+) -> ed::types::Sign {
+    use curve25519_dalek::constants::ED25519_BASEPOINT_TABLE;
+    use curve25519_dalek::scalar::Scalar;
+    use sha2::Digest;
+
+    let keypair = ed25519_keypair(seckey);
+    let expanded = ed25519_dalek::ExpandedSecretKey::from(&keypair.secret).to_bytes();
+    let mut key = [0; 32];
+    key.copy_from_slice(&expanded[..32]);
+    let key = Scalar::from_bits(key);
+    let dom2 = ed::verify::dom2(context.as_bytes()).unwrap();
+
+    let r = Scalar::from_hash(
+        sha2::Sha512::new()
+            .chain(&dom2)
+            .chain(&expanded[32..])
+            .chain(msg_bytes),
+    );
+    let big_r = (&r * &ED25519_BASEPOINT_TABLE).compress();
+    let k = Scalar::from_hash(
+        sha2::Sha512::new()
+            .chain(&dom2)
+            .chain(big_r.as_bytes())
+            .chain(keypair.public.as_bytes())
+            .chain(msg_bytes),
+    );
+    let s = k * key + r;
+
+    let mut res = [0; 64];
+    res[..32].copy_from_slice(big_r.as_bytes());
+    res[32..].copy_from_slice(s.as_bytes());
+    ed::types::Sign(res)
+}
+
+/// Signs the `msg` as Ethereum wallets do on `personal_sign`,
+/// followed by the recovery id, which is not offset by `27`.
+pub fn eth_personal_sign(seckey: ec::types::SecKey, msg: &str) -> ec::types::SignRecoverable {
+    use nearapps_exec::crypto::eth::personal_sign::PERSONAL_SIGN_PREFIX;
+
+    let signing_key = k256::ecdsa::SigningKey::from_bytes(&seckey.0).unwrap();
+    let sign: k256::ecdsa::recoverable::Signature = {
+        use k256::ecdsa::signature::DigestSigner;
+        use sha3::Digest;
+        signing_key.sign_digest(
+            sha3::Keccak256::new()
+                .chain(PERSONAL_SIGN_PREFIX)
+                .chain(msg.len().to_string())
+                .chain(msg),
+        )
+    };
+    sign.into()
+}
+
+/// Signs a `msg_hash` as for `Executor::verify_hashed_msg()`.
+pub fn sign_near(key: &KeyPair, msg_hash: &hash::Sha256) -> Bs58EncodedSignature {
+    let sign = match key.curve {
+        Curve::Ed25519 => eddsa_ed25519_sign(key.ed25519_seckey(), &msg_hash.0)
+            .0
+            .to_vec(),
+        Curve::Secp256k1 => ecdsa_secp256k1_sign_recoverable(key.secp256k1_seckey(), &msg_hash.0)
+            .0
+            .to_vec(),
+    };
+    Bs58EncodedSignature::encode(&sign)
+}
+
+/// Signs the `msg` as for `Executor::verify_msg()`.
+pub fn signed_msg(key: &KeyPair, msg: String) -> SignedMsg {
+    SignedMsg {
+        sign: sign_near(key, &sha256(msg.as_bytes())),
+        pubkey: key.near_public_key(),
+        msg,
+    }
+}
+
+/// Signs a `msg_hash` as for `Executor::verify_tagged_hashed_msg()`.
+///
+/// Panics if the `algorithm` is not for the curve of the `key`.
+pub fn sign_tagged(
+    key: &KeyPair,
+    algorithm: &Algorithm,
+    msg_hash: &hash::Sha256,
+) -> Bs58EncodedSignature {
+    let sign = match (algorithm, key.curve) {
+        (Algorithm::Ed25519, Curve::Ed25519) => {
+            eddsa_ed25519_sign(key.ed25519_seckey(), &msg_hash.0)
+                .0
+                .to_vec()
+        }
+        (Algorithm::Ed25519ph { context }, Curve::Ed25519) => {
+            let prehashed = hash::Sha512::hash_bytes(&msg_hash.0);
+            eddsa_ed25519_sign_prehashed(key.ed25519_seckey(), prehashed, context.as_deref())
+                .0
+                .to_vec()
+        }
+        (Algorithm::Ed25519ctx { context }, Curve::Ed25519) => {
+            eddsa_ed25519_sign_ctx(key.ed25519_seckey(), &msg_hash.0, context)
+                .0
+                .to_vec()
+        }
+        (Algorithm::EcdsaSecp256k1, Curve::Secp256k1) => {
+            ecdsa_secp256k1_sign(key.secp256k1_seckey(), &msg_hash.0)
+                .0
+                .to_vec()
+        }
+        (Algorithm::EcdsaSecp256k1Recoverable, Curve::Secp256k1) => {
+            ecdsa_secp256k1_sign_recoverable(key.secp256k1_seckey(), &msg_hash.0)
+                .0
+                .to_vec()
+        }
+        _ => panic!("the algorithm is not for the curve of the key"),
+    };
+    Bs58EncodedSignature::encode(&sign)
+}
@@ -1,9 +1,14 @@
 #!/bin/bash
 set -ex
 
-cargo +stable build --target wasm32-unknown-unknown --release
+# the cli is host-side only
+cargo +stable build --target wasm32-unknown-unknown --release --workspace --exclude nearapps-cli
 
 cp target/wasm32-unknown-unknown/release/nearapps_exec.wasm ./res/
 cp target/wasm32-unknown-unknown/release/nearapps_counter.wasm ./res/
 cp target/wasm32-unknown-unknown/release/nearapps_wallet.wasm ./res/
 cp target/wasm32-unknown-unknown/release/nearapps_nft.wasm ./res/
+
+# the executor that verifies signatures through host functions
+cargo +stable build --target wasm32-unknown-unknown --release -p nearapps-exec --features host_crypto
+cp target/wasm32-unknown-unknown/release/nearapps_exec.wasm ./res/nearapps_exec_host_crypto.wasm
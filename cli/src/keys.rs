@@ -0,0 +1,209 @@
+use nearapps_exec::crypto::{
+    ecdsa_secp256k1 as ec, ecdsa_secp256r1 as r1, eddsa_ed25519 as ed, eth, NearEncodedPubkey,
+};
+use std::convert::TryInto;
+use std::fmt;
+use std::str::FromStr;
+
+/// The curves that Near accounts can have keys on.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Curve {
+    Ed25519,
+    Secp256k1,
+}
+
+impl Curve {
+    /// The prefix of the Near encoding, eg. `ed25519` on
+    /// `ed25519:<base58>`.
+    pub fn prefix(&self) -> &'static str {
+        match self {
+            Curve::Ed25519 => "ed25519",
+            Curve::Secp256k1 => "secp256k1",
+        }
+    }
+}
+
+impl FromStr for Curve {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ed25519" => Ok(Curve::Ed25519),
+            "secp256k1" => Ok(Curve::Secp256k1),
+            _ => Err(format!("unknown curve: {}", s)),
+        }
+    }
+}
+
+/// A secret key, and the curve that it's on.
+///
+/// The secret key is private so that it's always valid for the
+/// curve, as checked when creating the `KeyPair`.
+#[derive(Clone, PartialEq)]
+pub struct KeyPair {
+    pub curve: Curve,
+    seckey: [u8; 32],
+}
+
+/// Shows the public key instead of the secret key.
+impl fmt::Debug for KeyPair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyPair")
+            .field("curve", &self.curve)
+            .field("public_key", &self.near_public_key())
+            .finish_non_exhaustive()
+    }
+}
+
+impl KeyPair {
+    /// Generates a new key from the os rng.
+    pub fn generate(curve: Curve) -> Result<Self, String> {
+        loop {
+            let mut seckey = [0; 32];
+            getrandom::getrandom(&mut seckey)
+                .map_err(|e| format!("failed to read from the os rng: {}", e))?;
+            // not every value is a valid secp256k1 key
+            if let Ok(key) = Self::from_seckey(curve, seckey) {
+                return Ok(key);
+            }
+        }
+    }
+
+    pub fn from_seckey(curve: Curve, seckey: [u8; 32]) -> Result<Self, String> {
+        if curve == Curve::Secp256k1 {
+            secp256k1_seckey(&ec::types::SecKey(seckey))?;
+        }
+        Ok(Self { curve, seckey })
+    }
+
+    /// Parses a Near encoded secret key, eg. `ed25519:<base58>` as
+    /// found on near-cli's credential files.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let (curve, data) = s
+            .split_once(':')
+            .ok_or_else(|| "missing the curve prefix".to_string())?;
+        let curve: Curve = curve.parse()?;
+        let bytes = near_sdk::bs58::decode(data)
+            .into_vec()
+            .map_err(|e| e.to_string())?;
+        let wrong_len = |bytes: Vec<u8>| format!("wrong secret key length of {}", bytes.len());
+        match curve {
+            // ed25519 secret keys are encoded together with their
+            // public key
+            Curve::Ed25519 => {
+                let seckey = ed::types::SecKeyExtended(bytes.try_into().map_err(wrong_len)?);
+                Self::from_seckey_extended(seckey)
+            }
+            Curve::Secp256k1 => Self::from_seckey(curve, bytes.try_into().map_err(wrong_len)?),
+        }
+    }
+
+    /// Uses the `seed` of an extended ed25519 secret key, as nearcore
+    /// has them, which must have the public key of that `seed`.
+    pub fn from_seckey_extended(seckey: ed::types::SecKeyExtended) -> Result<Self, String> {
+        if !seckey.is_consistent() {
+            return Err("the public key doesn't match the secret key".to_string());
+        }
+        Self::from_seckey(Curve::Ed25519, seckey.seed().0)
+    }
+
+    /// The Near encoding of the secret key, as near-cli stores it.
+    pub fn near_secret_key(&self) -> String {
+        let bytes = match self.curve {
+            Curve::Ed25519 => self.ed25519_seckey_extended().0.to_vec(),
+            Curve::Secp256k1 => self.seckey.to_vec(),
+        };
+        format!(
+            "{}:{}",
+            self.curve.prefix(),
+            near_sdk::bs58::encode(bytes).into_string()
+        )
+    }
+
+    /// The Near encoding of the public key.
+    pub fn near_public_key(&self) -> NearEncodedPubkey {
+        match self.curve {
+            Curve::Ed25519 => {
+                let pubkey: near_sdk::PublicKey = ed25519_pubkey(self.ed25519_seckey())
+                    .try_into()
+                    .expect("ed25519 public keys have 32 bytes");
+                String::from(&pubkey).into()
+            }
+            Curve::Secp256k1 => {
+                let pubkey = k256::SecretKey::from_bytes(&self.seckey)
+                    .expect("checked on creation")
+                    .public_key();
+                let pubkey: ec::types::PubKeyUncompressedNoHeader = pubkey.into();
+                pubkey.into()
+            }
+        }
+    }
+
+    pub fn ed25519_seckey(&self) -> ed::types::SecKey {
+        ed::types::SecKey(self.seckey)
+    }
+
+    /// The secret key in the extended form, with the public key.
+    pub fn ed25519_seckey_extended(&self) -> ed::types::SecKeyExtended {
+        self.ed25519_seckey().into()
+    }
+
+    pub fn secp256k1_seckey(&self) -> ec::types::SecKey {
+        ec::types::SecKey(self.seckey)
+    }
+}
+
+/// Creates a Public Key serialized in compressed form.
+///
+/// Has a total size of 33 bytes.
+pub fn secp256k1_pubkey_compressed(
+    seckey: ec::types::SecKey,
+) -> Result<ec::types::PubKeyCompressed, String> {
+    let seckey = secp256k1_seckey(&seckey)?;
+    Ok(seckey.public_key().into())
+}
+
+/// Creates a Public Key serialized in uncompressed form.
+///
+/// Has a total size of 65 bytes.
+pub fn secp256k1_pubkey_uncompressed(
+    seckey: ec::types::SecKey,
+) -> Result<ec::types::PubKeyUncompressed, String> {
+    let seckey = secp256k1_seckey(&seckey)?;
+    Ok(seckey.public_key().into())
+}
+
+/// Creates the Ethereum address of the key, with the EIP-55
+/// checksum.
+pub fn secp256k1_eth_address(
+    seckey: ec::types::SecKey,
+) -> Result<eth::types::ChecksumAddress, String> {
+    let pubkey = secp256k1_pubkey_uncompressed(seckey)?;
+    Ok(eth::types::Address::from(pubkey).into())
+}
+
+/// Creates a secp256r1 (P-256) Public Key serialized in
+/// uncompressed form, as WebAuthn credentials have.
+///
+/// Has a total size of 65 bytes.
+pub fn secp256r1_pubkey_uncompressed(
+    seckey: r1::types::SecKey,
+) -> Result<r1::types::PubKeyUncompressed, String> {
+    let seckey = secp256r1_seckey(&seckey)?;
+    Ok(seckey.public_key().into())
+}
+
+pub fn ed25519_pubkey(seckey: ed::types::SecKey) -> ed::types::PubKey {
+    (&seckey).into()
+}
+
+/// Parses a secp256k1 secret key, which must be in the range of the
+/// curve order.
+pub(crate) fn secp256k1_seckey(seckey: &ec::types::SecKey) -> Result<k256::SecretKey, String> {
+    k256::SecretKey::from_bytes(&seckey.0).map_err(|_| "invalid secp256k1 secret key".to_string())
+}
+
+/// Parses a secp256r1 secret key, which must be in the range of the
+/// curve order.
+pub(crate) fn secp256r1_seckey(seckey: &r1::types::SecKey) -> Result<p256::SecretKey, String> {
+    p256::SecretKey::from_bytes(&seckey.0).map_err(|_| "invalid secp256r1 secret key".to_string())
+}
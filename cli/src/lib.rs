@@ -0,0 +1,10 @@
+//! Host-side key management and signing for the executor.
+//!
+//! Secret keys never leave the host: keys are generated and messages
+//! are signed here, and only the public keys and signatures are sent
+//! to the contract.
+
+pub mod keys;
+pub mod merkle;
+pub mod payload;
+pub mod sign;
@@ -0,0 +1,167 @@
+//! Offline key management and signing for the executor.
+//!
+//! Run `nearapps help` for the commands.
+
+use near_sdk::serde_json::{self, json};
+use nearapps_cli::keys::{
+    secp256k1_eth_address, secp256k1_pubkey_compressed, secp256k1_pubkey_uncompressed, Curve,
+    KeyPair,
+};
+use nearapps_cli::merkle::MerkleTree;
+use nearapps_cli::{payload, sign};
+use nearapps_exec::crypto::algorithm::Algorithm;
+use nearapps_exec::crypto::envelope::Envelope;
+use nearapps_exec::exec::CallContext;
+use nearapps_exec::hash;
+use nearapps_exec::hash::merkle::{MerkleHash, MerkleNode};
+use std::convert::TryInto;
+
+const USAGE: &str = "\
+usage: nearapps <command> [args]
+
+Secret keys are Near encoded, eg. `ed25519:<base58>`, as in near-cli's
+credential files. Hashes are hex encoded.
+
+commands:
+    keygen <ed25519|secp256k1>
+        generates a new keypair.
+    pubkey <secret-key>
+        prints the public key, and the ethereum address of secp256k1
+        keys.
+    sign <secret-key> <msg>
+        signs the sha256 of msg, as `verify_msg` expects.
+    sign-hash <secret-key> <sha256>
+        signs a sha256 hash, as `verify_hashed_msg` expects.
+    verify-msg <secret-key> <msg>
+        prints the arguments for `verify_msg`.
+    verify-hashed-msg <secret-key> <sha256>
+        prints the arguments for `verify_hashed_msg`.
+    verify-tagged-msg <secret-key> <algorithm-json> <msg>
+        signs the sha256 of msg with the algorithm, eg.
+        `{\"name\":\"ed25519ctx\",\"context\":\"app\"}`, and prints the
+        arguments for `verify_tagged_msg`.
+    verify-by-eth-address <secret-key> <msg>
+        signs msg with `personal_sign` and a secp256k1 key and prints
+        the arguments for `verify_by_eth_address`.
+    execute <secret-key> <context-json>
+        prints the arguments for `execute`, together with the
+        signature of the context.
+    envelope <secret-key> <envelope-json>
+        signs the envelope and prints the arguments for
+        `verify_envelope`.
+    merkle-proof <sha256|keccak256> <sorted|indexed> <index> <leaf>...
+        builds the merkle tree of the leaves, which are hashes, and
+        prints the arguments for `verify_merkle_proof` of the leaf at
+        index.
+";
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    match run(&args) {
+        Ok(output) => println!("{}", serde_json::to_string_pretty(&output).unwrap()),
+        Err(err) => {
+            eprintln!("error: {}\n\n{}", err, USAGE);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn run(args: &[&str]) -> Result<serde_json::Value, String> {
+    let res = match args {
+        ["keygen", curve] => {
+            let key = KeyPair::generate(curve.parse()?)?;
+            json!({
+                "public_key": key.near_public_key(),
+                "private_key": key.near_secret_key(),
+            })
+        }
+        ["pubkey", seckey] => {
+            let key = KeyPair::parse(seckey)?;
+            match key.curve {
+                Curve::Ed25519 => json!({ "public_key": key.near_public_key() }),
+                Curve::Secp256k1 => json!({
+                    "public_key": key.near_public_key(),
+                    "compressed": hex::encode(secp256k1_pubkey_compressed(key.secp256k1_seckey())?.0),
+                    "uncompressed": hex::encode(secp256k1_pubkey_uncompressed(key.secp256k1_seckey())?.0),
+                    "eth_address": secp256k1_eth_address(key.secp256k1_seckey())?,
+                }),
+            }
+        }
+        ["sign", seckey, msg] => {
+            let key = KeyPair::parse(seckey)?;
+            let msg_hash = payload::sha256(msg.as_bytes());
+            json!({ "sign": sign::sign_near(&key, &msg_hash)? })
+        }
+        ["sign-hash", seckey, msg_hash] => {
+            let key = KeyPair::parse(seckey)?;
+            json!({ "sign": sign::sign_near(&key, &parse_sha256(msg_hash)?)? })
+        }
+        ["verify-msg", seckey, msg] => {
+            let key = KeyPair::parse(seckey)?;
+            json!(payload::verify_msg(&key, msg.to_string())?)
+        }
+        ["verify-hashed-msg", seckey, msg_hash] => {
+            let key = KeyPair::parse(seckey)?;
+            json!(payload::verify_hashed_msg(&key, parse_sha256(msg_hash)?)?)
+        }
+        ["verify-tagged-msg", seckey, algorithm, msg] => {
+            let key = KeyPair::parse(seckey)?;
+            let algorithm: Algorithm =
+                serde_json::from_str(algorithm).map_err(|e| format!("invalid algorithm: {}", e))?;
+            let args = payload::verify_tagged_msg(&key, algorithm, msg.to_string())?;
+            json!(args)
+        }
+        ["verify-by-eth-address", seckey, msg] => {
+            let key = KeyPair::parse(seckey)?;
+            json!(payload::verify_by_eth_address(&key, msg.to_string())?)
+        }
+        ["execute", seckey, context] => {
+            let key = KeyPair::parse(seckey)?;
+            let context: CallContext =
+                serde_json::from_str(context).map_err(|e| format!("invalid context: {}", e))?;
+            json!(payload::execute(&key, context)?)
+        }
+        ["envelope", seckey, envelope] => {
+            let key = KeyPair::parse(seckey)?;
+            let envelope: Envelope =
+                serde_json::from_str(envelope).map_err(|e| format!("invalid envelope: {}", e))?;
+            json!(payload::verify_envelope(&key, envelope)?)
+        }
+        ["merkle-proof", hash, pairing, index, leaves @ ..] => {
+            let hash = match *hash {
+                "sha256" => MerkleHash::Sha256,
+                "keccak256" => MerkleHash::Keccak256,
+                _ => return Err(format!("unknown hash: {}", hash)),
+            };
+            let leaves = leaves
+                .iter()
+                .map(|leaf| parse_sha256(leaf).map(|leaf| MerkleNode(leaf.0)))
+                .collect::<Result<Vec<_>, _>>()?;
+            let tree = match *pairing {
+                "sorted" => MerkleTree::sorted(hash, leaves)?,
+                "indexed" => MerkleTree::indexed(hash, leaves)?,
+                _ => return Err(format!("unknown pairing: {}", pairing)),
+            };
+            let index = index.parse().map_err(|_| "invalid index".to_string())?;
+            let args = payload::verify_merkle_proof(&tree, index)
+                .ok_or_else(|| "the index is out of the leaves".to_string())?;
+            json!(args)
+        }
+        ["help"] | ["--help"] | ["-h"] => {
+            print!("{}", USAGE);
+            std::process::exit(0);
+        }
+        _ => return Err("unknown command or wrong arguments".to_string()),
+    };
+    Ok(res)
+}
+
+fn parse_sha256(s: &str) -> Result<hash::Sha256, String> {
+    let bytes = hex::decode(s.trim_start_matches("0x")).map_err(|e| e.to_string())?;
+    let bytes: [u8; 32] = bytes
+        .as_slice()
+        .try_into()
+        .map_err(|_| "the hash must have 32 bytes".to_string())?;
+    Ok(hash::Sha256(bytes))
+}
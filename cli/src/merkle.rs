@@ -0,0 +1,129 @@
+//! Building Merkle trees, and their proofs for
+//! `Executor::verify_merkle_proof()`.
+
+use nearapps_exec::hash::merkle::{MerkleHash, MerkleNode, MerkleProof, Pairing};
+use std::convert::TryFrom;
+
+#[derive(Clone, PartialEq, Debug)]
+enum Layout {
+    /// All nodes in a single array, as OpenZeppelin's
+    /// `StandardMerkleTree` lays them out: the children of the node `i`
+    /// are at `2i + 1` and `2i + 2`, and the leaves are at the end, in
+    /// reverse order.
+    ///
+    /// This is a complete tree, so every node but the root has a
+    /// sibling.
+    Sorted(Vec<MerkleNode>),
+    /// The levels of the tree, from the leaves up to the root.
+    ///
+    /// The leaves are padded with zeroed nodes up to a power of two.
+    Indexed(Vec<Vec<MerkleNode>>),
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct MerkleTree {
+    hash: MerkleHash,
+    leaves: Vec<MerkleNode>,
+    layout: Layout,
+}
+
+impl MerkleTree {
+    /// Builds a tree of sorted pairs, whose proofs are compatible with
+    /// OpenZeppelin's `MerkleProof`.
+    ///
+    /// For the same root as OpenZeppelin's `StandardMerkleTree`, the
+    /// `leaves` must already be sorted, and be hashed as it does.
+    pub fn sorted(hash: MerkleHash, leaves: Vec<MerkleNode>) -> Result<Self, String> {
+        if leaves.is_empty() {
+            return Err("the tree must have at least one leaf".to_string());
+        }
+        let len = 2 * leaves.len() - 1;
+        let mut nodes = vec![MerkleNode::default(); len];
+        for (i, leaf) in leaves.iter().enumerate() {
+            nodes[len - 1 - i] = leaf.clone();
+        }
+        for i in (0..len - leaves.len()).rev() {
+            nodes[i] = hash.hash_sorted_pair(&nodes[2 * i + 1], &nodes[2 * i + 2]);
+        }
+        Ok(Self {
+            hash,
+            leaves,
+            layout: Layout::Sorted(nodes),
+        })
+    }
+
+    /// Builds a tree of indexed pairs, whose proofs also prove the
+    /// position of the leaf.
+    ///
+    /// The zeroed nodes that pad the leaves can also be proven, so the
+    /// leaves should be hashes that can't be zero.
+    pub fn indexed(hash: MerkleHash, leaves: Vec<MerkleNode>) -> Result<Self, String> {
+        if leaves.is_empty() {
+            return Err("the tree must have at least one leaf".to_string());
+        }
+        if u32::try_from(leaves.len() - 1).is_err() {
+            return Err("the tree has too many leaves".to_string());
+        }
+        let mut level = leaves.clone();
+        level.resize(leaves.len().next_power_of_two(), MerkleNode::default());
+        let mut levels = vec![level];
+        while levels.last().unwrap().len() > 1 {
+            let level = levels
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| hash.hash_pair(&pair[0], &pair[1]))
+                .collect();
+            levels.push(level);
+        }
+        Ok(Self {
+            hash,
+            leaves,
+            layout: Layout::Indexed(levels),
+        })
+    }
+
+    pub fn root(&self) -> MerkleNode {
+        match &self.layout {
+            Layout::Sorted(nodes) => nodes[0].clone(),
+            Layout::Indexed(levels) => levels.last().unwrap()[0].clone(),
+        }
+    }
+
+    pub fn leaves(&self) -> &[MerkleNode] {
+        &self.leaves
+    }
+
+    /// The proof for the leaf at `index`, if there is one.
+    pub fn proof(&self, index: usize) -> Option<MerkleProof> {
+        if index >= self.leaves.len() {
+            return None;
+        }
+        let (pairing, siblings) = match &self.layout {
+            Layout::Sorted(nodes) => {
+                let mut siblings = vec![];
+                let mut i = nodes.len() - 1 - index;
+                while i > 0 {
+                    let sibling = if i % 2 == 1 { i + 1 } else { i - 1 };
+                    siblings.push(nodes[sibling].clone());
+                    i = (i - 1) / 2;
+                }
+                (Pairing::Sorted, siblings)
+            }
+            Layout::Indexed(levels) => {
+                let siblings = levels[..levels.len() - 1]
+                    .iter()
+                    .enumerate()
+                    .map(|(height, level)| level[(index >> height) ^ 1].clone())
+                    .collect();
+                // the leaves fit in an u32
+                (Pairing::Indexed(index as u32), siblings)
+            }
+        };
+        Some(MerkleProof {
+            hash: self.hash,
+            pairing,
+            siblings,
+        })
+    }
+}
@@ -0,0 +1,176 @@
+//! Arguments, ready to be sent as json, for the executor's methods.
+
+use crate::keys::{secp256k1_eth_address, Curve, KeyPair};
+use crate::merkle::MerkleTree;
+use crate::sign::{self, sign_near, sign_tagged};
+use near_sdk::serde::Serialize;
+use nearapps_exec::crypto::algorithm::Algorithm;
+use nearapps_exec::crypto::envelope::Envelope;
+use nearapps_exec::crypto::{eth, Bs58EncodedSignature, NearEncodedPubkey, SignedMsg};
+use nearapps_exec::exec::CallContext;
+use nearapps_exec::hash;
+use nearapps_exec::hash::merkle::{MerkleNode, MerkleProof};
+
+/// Arguments for `Executor::verify_msg()`.
+#[derive(Serialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct VerifyMsgArgs {
+    pub sign: Bs58EncodedSignature,
+    pub pubkey: NearEncodedPubkey,
+    pub msg: String,
+}
+
+/// Arguments for `Executor::verify_hashed_msg()`.
+#[derive(Serialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct VerifyHashedMsgArgs {
+    pub sign: Bs58EncodedSignature,
+    pub pubkey: NearEncodedPubkey,
+    pub msg_hash: hash::Sha256,
+}
+
+/// Arguments for `Executor::verify_tagged_msg()`.
+#[derive(Serialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct VerifyTaggedMsgArgs {
+    pub algorithm: Algorithm,
+    pub sign: Bs58EncodedSignature,
+    pub pubkey: NearEncodedPubkey,
+    pub msg: String,
+}
+
+/// Arguments for `Executor::verify_by_eth_address()`.
+#[derive(Serialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct VerifyByEthAddressArgs {
+    pub address: eth::types::ChecksumAddress,
+    pub sign: Bs58EncodedSignature,
+    pub msg: String,
+}
+
+/// Arguments for `Executor::verify_envelope()`.
+#[derive(Serialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct VerifyEnvelopeArgs {
+    pub envelope: Envelope,
+    pub sign: Bs58EncodedSignature,
+    pub pubkey: NearEncodedPubkey,
+    pub domain: String,
+}
+
+/// Arguments for `Executor::verify_merkle_proof()`.
+#[derive(Serialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct VerifyMerkleProofArgs {
+    pub root: MerkleNode,
+    pub leaf: MerkleNode,
+    pub proof: MerkleProof,
+}
+
+/// Arguments for `Executor::execute()`.
+#[derive(Serialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ExecuteArgs {
+    pub context: CallContext,
+}
+
+/// The `execute` arguments, together with a signature of the
+/// `context` by the user's key.
+#[derive(Serialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SignedExecute {
+    pub args: ExecuteArgs,
+    /// The `msg` is the `context` as json, so this can be checked
+    /// with `Executor::verify_msg()`.
+    pub signed: SignedMsg,
+}
+
+/// The `sha256` of `msg_bytes`, computed off-chain.
+pub fn sha256(msg_bytes: &[u8]) -> hash::Sha256 {
+    use sha2::Digest;
+    let hash = sha2::Sha256::digest(msg_bytes);
+    let mut res = [0; 32];
+    res.copy_from_slice(hash.as_slice());
+    hash::Sha256(res)
+}
+
+pub fn verify_msg(key: &KeyPair, msg: String) -> Result<VerifyMsgArgs, String> {
+    let msg_hash = sha256(msg.as_bytes());
+    Ok(VerifyMsgArgs {
+        sign: sign_near(key, &msg_hash)?,
+        pubkey: key.near_public_key(),
+        msg,
+    })
+}
+
+pub fn verify_hashed_msg(
+    key: &KeyPair,
+    msg_hash: hash::Sha256,
+) -> Result<VerifyHashedMsgArgs, String> {
+    Ok(VerifyHashedMsgArgs {
+        sign: sign_near(key, &msg_hash)?,
+        pubkey: key.near_public_key(),
+        msg_hash,
+    })
+}
+
+/// Signs the `sha256` of the `msg` with the `algorithm`, which must
+/// be for the curve of the `key`.
+pub fn verify_tagged_msg(
+    key: &KeyPair,
+    algorithm: Algorithm,
+    msg: String,
+) -> Result<VerifyTaggedMsgArgs, String> {
+    let msg_hash = sha256(msg.as_bytes());
+    Ok(VerifyTaggedMsgArgs {
+        sign: sign_tagged(key, &algorithm, &msg_hash)?,
+        pubkey: key.near_public_key(),
+        algorithm,
+        msg,
+    })
+}
+
+/// Signs the `msg` with `personal_sign` and a secp256k1 `key`, which
+/// is identified by its Ethereum address.
+pub fn verify_by_eth_address(key: &KeyPair, msg: String) -> Result<VerifyByEthAddressArgs, String> {
+    if key.curve != Curve::Secp256k1 {
+        return Err("the key must be a secp256k1 key".to_string());
+    }
+    let sign = sign::eth_personal_sign(key.secp256k1_seckey(), &msg)?;
+    Ok(VerifyByEthAddressArgs {
+        address: secp256k1_eth_address(key.secp256k1_seckey())?,
+        sign: Bs58EncodedSignature::encode(&sign.0),
+        msg,
+    })
+}
+
+/// Signs the `envelope`, which is checked against its own `domain`.
+///
+/// The executor checks the `chain_id` against its own account, so
+/// the `contract_id` must be on that chain.
+pub fn verify_envelope(key: &KeyPair, envelope: Envelope) -> Result<VerifyEnvelopeArgs, String> {
+    Ok(VerifyEnvelopeArgs {
+        sign: sign_near(key, &envelope.hash())?,
+        pubkey: key.near_public_key(),
+        domain: envelope.domain.clone(),
+        envelope,
+    })
+}
+
+/// The proof for the leaf of `tree` at `index`, if there is one.
+pub fn verify_merkle_proof(tree: &MerkleTree, index: usize) -> Option<VerifyMerkleProofArgs> {
+    Some(VerifyMerkleProofArgs {
+        root: tree.root(),
+        leaf: tree.leaves().get(index)?.clone(),
+        proof: tree.proof(index)?,
+    })
+}
+
+pub fn execute(key: &KeyPair, context: CallContext) -> Result<SignedExecute, String> {
+    let msg = near_sdk::serde_json::to_string(&context).map_err(|e| e.to_string())?;
+    let VerifyMsgArgs { sign, pubkey, msg } = verify_msg(key, msg)?;
+    Ok(SignedExecute {
+        args: ExecuteArgs { context },
+        signed: SignedMsg { sign, pubkey, msg },
+    })
+}
@@ -0,0 +1,280 @@
+use crate::keys::{secp256k1_seckey, secp256r1_seckey, Curve, KeyPair};
+use nearapps_exec::crypto::algorithm::Algorithm;
+use nearapps_exec::crypto::{
+    ecdsa_secp256k1 as ec, ecdsa_secp256r1 as r1, eddsa_ed25519 as ed, Bs58EncodedSignature,
+};
+use nearapps_exec::hash;
+
+/// Creates a `sha256` hash of the `msg_bytes` and signs it
+/// using `ecdsa` on `secp256k1`.
+///
+/// Signing is deterministic and the "pseudo-random" value `k` depends
+/// only on the hash of the combination of `seckey` and the hash of
+/// `msg_bytes`.
+/// See [rfc6979](https://datatracker.ietf.org/doc/html/rfc6979) for more info.
+///
+/// To avoid generating signatures that may have malleability issues,
+/// they are explicitly
+/// [normalized](k256::ecdsa::Signature::normalize_s()) to
+/// the lower-S form.
+///
+/// Returns the signature in serialized compact form.
+/// Has a total size of 64-bytes.
+pub fn ecdsa_secp256k1_sign(
+    seckey: ec::types::SecKey,
+    msg_bytes: &[u8],
+) -> Result<ec::types::SignCompact, String> {
+    let signing_key = k256::ecdsa::SigningKey::from(secp256k1_seckey(&seckey)?);
+    let mut sign: k256::ecdsa::Signature = {
+        use k256::ecdsa::signature::DigestSigner;
+        use sha2::Digest;
+        let digest = digest::Digest::chain(sha2::Sha256::new(), msg_bytes);
+        signing_key.try_sign_digest(digest).map_err(sign_failed)?
+    };
+    sign.normalize_s().map_err(sign_failed)?;
+    Ok(sign.into())
+}
+
+/// Creates a `sha256` hash of the `msg_bytes` and signs it
+/// using `ecdsa` on `secp256k1`.
+/// This creates a recoverable signature, ie. the PublicKey
+/// can be recovered from the signature and the original message.
+///
+/// Signing is deterministic, as on [`ecdsa_secp256k1_sign()`], and
+/// the signatures are already in the lower-S form.
+///
+/// Returns the signature in serialized form, followed by the
+/// recovery id.
+/// Has a total size of 65-bytes.
+pub fn ecdsa_secp256k1_sign_recoverable(
+    seckey: ec::types::SecKey,
+    msg_bytes: &[u8],
+) -> Result<ec::types::SignRecoverable, String> {
+    let signing_key = k256::ecdsa::SigningKey::from(secp256k1_seckey(&seckey)?);
+    let sign: k256::ecdsa::recoverable::Signature = {
+        use k256::ecdsa::signature::DigestSigner;
+        use sha2::Digest;
+        let digest = digest::Digest::chain(sha2::Sha256::new(), msg_bytes);
+        signing_key.try_sign_digest(digest).map_err(sign_failed)?
+    };
+
+    // checks it's in the lower-s form
+    let without_recovery_id: k256::ecdsa::Signature = sign.into();
+    if bool::from(without_recovery_id.s().is_high()) {
+        return Err("the signature is not in the lower-S form".to_string());
+    }
+
+    Ok(sign.into())
+}
+
+/// Signs the `msg` as Ethereum wallets do on `personal_sign`, ie.
+/// the `keccak256` hash of the `msg` with the EIP-191 prefix, using
+/// `ecdsa` on `secp256k1`.
+///
+/// Signing is deterministic, as on [`ecdsa_secp256k1_sign()`], and
+/// the signatures are in the lower-S form.
+///
+/// Returns the signature in serialized form, followed by the
+/// recovery id, which is not offset by `27`.
+/// Has a total size of 65-bytes.
+pub fn eth_personal_sign(
+    seckey: ec::types::SecKey,
+    msg: &str,
+) -> Result<ec::types::SignRecoverable, String> {
+    use nearapps_exec::crypto::eth::personal_sign::PERSONAL_SIGN_PREFIX;
+
+    let signing_key = k256::ecdsa::SigningKey::from(secp256k1_seckey(&seckey)?);
+    let sign: k256::ecdsa::recoverable::Signature = {
+        use k256::ecdsa::signature::DigestSigner;
+        use sha3::Digest;
+        let digest = sha3::Keccak256::new()
+            .chain(PERSONAL_SIGN_PREFIX)
+            .chain(msg.len().to_string())
+            .chain(msg);
+        signing_key.try_sign_digest(digest).map_err(sign_failed)?
+    };
+
+    let without_recovery_id: k256::ecdsa::Signature = sign.into();
+    if bool::from(without_recovery_id.s().is_high()) {
+        return Err("the signature is not in the lower-S form".to_string());
+    }
+
+    Ok(sign.into())
+}
+
+/// Creates a `sha256` hash of the `msg_bytes` and signs it
+/// using `ecdsa` on `secp256r1` (P-256).
+///
+/// Signing is deterministic, as on [`ecdsa_secp256k1_sign()`], but
+/// the signature is not normalized, the same as WebAuthn
+/// authenticators.
+///
+/// Returns the signature in serialized compact form.
+/// Has a total size of 64-bytes.
+pub fn ecdsa_secp256r1_sign(
+    seckey: r1::types::SecKey,
+    msg_bytes: &[u8],
+) -> Result<r1::types::SignCompact, String> {
+    let signing_key = p256::ecdsa::SigningKey::from(secp256r1_seckey(&seckey)?);
+    let sign: p256::ecdsa::Signature = {
+        use p256::ecdsa::signature::DigestSigner;
+        use sha2::Digest;
+        let digest = digest::Digest::chain(sha2::Sha256::new(), msg_bytes);
+        signing_key.try_sign_digest(digest).map_err(sign_failed)?
+    };
+    Ok(sign.into())
+}
+
+pub fn eddsa_ed25519_sign(seckey: ed::types::SecKey, msg_bytes: &[u8]) -> ed::types::Sign {
+    let keypair = ed25519_keypair(seckey);
+    let sign: ed25519_dalek::Signature = {
+        use ed25519_dalek::Signer;
+        keypair.sign(msg_bytes)
+    };
+    sign.into()
+}
+
+/// Signs the `msg_bytes` with an extended secret key, as nearcore
+/// has them.
+///
+/// Fails if the public key of `seckey` doesn't match its `seed`, as
+/// the signature would not be verifiable.
+pub fn eddsa_ed25519_sign_extended(
+    seckey: ed::types::SecKeyExtended,
+    msg_bytes: &[u8],
+) -> Result<ed::types::Sign, String> {
+    if !seckey.is_consistent() {
+        return Err("the public key doesn't match the secret key".to_string());
+    }
+    Ok(eddsa_ed25519_sign(seckey.seed(), msg_bytes))
+}
+
+/// Signs the `msg_hash` with the `Ed25519ph` algorithm.
+///
+/// The `context` must have at most 255 bytes.
+pub fn eddsa_ed25519_sign_prehashed(
+    seckey: ed::types::SecKey,
+    msg_hash: hash::Sha512,
+    context: Option<&str>,
+) -> Result<ed::types::SignPrehashed, String> {
+    let keypair = ed25519_keypair(seckey);
+    let context = context.map(|s| s.as_bytes());
+    let sign: ed25519_dalek::Signature = keypair
+        .sign_prehashed(msg_hash, context)
+        .map_err(|_| "the context must have at most 255 bytes".to_string())?;
+    Ok(sign.into())
+}
+
+/// Signs the `msg_bytes` with the `Ed25519ctx` algorithm, which
+/// binds the signature to the `context`.
+///
+/// The `context` must have between 1 and 255 bytes.
+pub fn eddsa_ed25519_sign_ctx(
+    seckey: ed::types::SecKey,
+    msg_bytes: &[u8],
+    context: &str,
+) -> Result<ed::types::Sign, String> {
+    use curve25519_dalek::constants::ED25519_BASEPOINT_TABLE;
+    use curve25519_dalek::scalar::Scalar;
+    use sha2::Digest;
+
+    if context.is_empty() || context.len() > 255 {
+        return Err("the context must have between 1 and 255 bytes".to_string());
+    }
+    let keypair = ed25519_keypair(seckey);
+    let expanded = ed25519_dalek::ExpandedSecretKey::from(&keypair.secret).to_bytes();
+    let mut key = [0; 32];
+    key.copy_from_slice(&expanded[..32]);
+    let key = Scalar::from_bits(key);
+    let dom2 = ed::verify::dom2(context.as_bytes()).map_err(|e| e.to_string())?;
+
+    let r = Scalar::from_hash(
+        sha2::Sha512::new()
+            .chain(&dom2)
+            .chain(&expanded[32..])
+            .chain(msg_bytes),
+    );
+    let big_r = (&r * &ED25519_BASEPOINT_TABLE).compress();
+    let k = Scalar::from_hash(
+        sha2::Sha512::new()
+            .chain(&dom2)
+            .chain(big_r.as_bytes())
+            .chain(keypair.public.as_bytes())
+            .chain(msg_bytes),
+    );
+    let s = k * key + r;
+
+    let mut res = [0; 64];
+    res[..32].copy_from_slice(big_r.as_bytes());
+    res[32..].copy_from_slice(s.as_bytes());
+    Ok(ed::types::Sign(res))
+}
+
+fn ed25519_keypair(seckey: ed::types::SecKey) -> ed25519_dalek::Keypair {
+    let secret =
+        ed25519_dalek::SecretKey::from_bytes(&seckey.0).expect("any 32 bytes are a secret key");
+    let public: ed25519_dalek::PublicKey = (&secret).into();
+    ed25519_dalek::Keypair { secret, public }
+}
+
+fn sign_failed(e: impl std::fmt::Display) -> String {
+    format!("failed to sign: {}", e)
+}
+
+/// Signs a `msg_hash` so that it's accepted by
+/// `Executor::verify_hashed_msg()`.
+///
+/// As on Near, the `msg_hash` is what gets signed, so it's hashed
+/// again by `secp256k1` and by `ed25519`.
+pub fn sign_near(key: &KeyPair, msg_hash: &hash::Sha256) -> Result<Bs58EncodedSignature, String> {
+    let sign = match key.curve {
+        Curve::Ed25519 => eddsa_ed25519_sign(key.ed25519_seckey(), &msg_hash.0)
+            .0
+            .to_vec(),
+        Curve::Secp256k1 => ecdsa_secp256k1_sign_recoverable(key.secp256k1_seckey(), &msg_hash.0)?
+            .0
+            .to_vec(),
+    };
+    Ok(Bs58EncodedSignature::encode(&sign))
+}
+
+/// Signs a `msg_hash` with the `algorithm`, so that it's accepted by
+/// `Executor::verify_tagged_hashed_msg()`.
+///
+/// Fails if the `algorithm` is not for the curve of the `key`.
+pub fn sign_tagged(
+    key: &KeyPair,
+    algorithm: &Algorithm,
+    msg_hash: &hash::Sha256,
+) -> Result<Bs58EncodedSignature, String> {
+    let sign = match (algorithm, key.curve) {
+        (Algorithm::Ed25519, Curve::Ed25519) => {
+            eddsa_ed25519_sign(key.ed25519_seckey(), &msg_hash.0)
+                .0
+                .to_vec()
+        }
+        (Algorithm::Ed25519ph { context }, Curve::Ed25519) => {
+            let prehashed = hash::Sha512::hash_bytes(&msg_hash.0);
+            eddsa_ed25519_sign_prehashed(key.ed25519_seckey(), prehashed, context.as_deref())?
+                .0
+                .to_vec()
+        }
+        (Algorithm::Ed25519ctx { context }, Curve::Ed25519) => {
+            eddsa_ed25519_sign_ctx(key.ed25519_seckey(), &msg_hash.0, context)?
+                .0
+                .to_vec()
+        }
+        (Algorithm::EcdsaSecp256k1, Curve::Secp256k1) => {
+            ecdsa_secp256k1_sign(key.secp256k1_seckey(), &msg_hash.0)?
+                .0
+                .to_vec()
+        }
+        (Algorithm::EcdsaSecp256k1Recoverable, Curve::Secp256k1) => {
+            ecdsa_secp256k1_sign_recoverable(key.secp256k1_seckey(), &msg_hash.0)?
+                .0
+                .to_vec()
+        }
+        _ => return Err("the algorithm is not for the curve of the key".to_string()),
+    };
+    Ok(Bs58EncodedSignature::encode(&sign))
+}
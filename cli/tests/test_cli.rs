@@ -0,0 +1,236 @@
+#![allow(clippy::ref_in_deref)]
+
+use crate::utils::setup_exec;
+use near_sdk_sim::{call, init_simulator};
+use nearapps_cli::keys::{Curve, KeyPair};
+use nearapps_cli::payload;
+use nearapps_exec::crypto::{Bs58EncodedSignature, NearEncodedPubkey};
+use nearapps_exec::exec::{CallContext, ContractCall, TagInfo};
+use std::convert::TryInto;
+
+mod utils;
+
+/// The payloads created off-chain are accepted by the contract.
+#[test]
+fn test_cli_payloads() {
+    let root = init_simulator(None);
+    let contract = setup_exec(&root);
+
+    for curve in [Curve::Ed25519, Curve::Secp256k1] {
+        let key = KeyPair::generate(curve).unwrap();
+
+        // ok: the encoded secret key round-trips
+        assert_eq!(KeyPair::parse(&key.near_secret_key()).unwrap(), key);
+
+        // ok: the debug output doesn't show the secret key
+        let secret = key.near_secret_key();
+        let secret = secret.split_once(':').unwrap().1;
+        assert!(!format!("{:?}", key).contains(secret));
+
+        // ok: verify_msg
+        let args = payload::verify_msg(&key, "This is some message".to_string()).unwrap();
+        let verify1: bool = call!(
+            &root,
+            contract.verify_msg(args.sign, args.pubkey, args.msg, None)
+        )
+        .unwrap_json();
+        assert!(verify1);
+
+        // ok: verify_hashed_msg
+        let msg_hash = payload::sha256(b"This is some message");
+        let args = payload::verify_hashed_msg(&key, msg_hash).unwrap();
+        let verify2: bool = call!(
+            &root,
+            contract.verify_hashed_msg(args.sign, args.pubkey, args.msg_hash, None)
+        )
+        .unwrap_json();
+        assert!(verify2);
+
+        // ok: the signed execute context
+        let context = CallContext {
+            contract_call: ContractCall {
+                contract_id: "counter".parse().unwrap(),
+                method_name: "increment".into(),
+                args: "".into(),
+            },
+            tag_info: TagInfo {
+                app_id: "the_app_id".into(),
+                action_id: 0.into(),
+                user_id: "user.id".parse().unwrap(),
+            },
+        };
+        let signed = payload::execute(&key, context).unwrap().signed;
+        let verify3: bool = call!(
+            &root,
+            contract.verify_msg(signed.sign, signed.pubkey, signed.msg, None)
+        )
+        .unwrap_json();
+        assert!(verify3);
+
+        // fail: a different key
+        let other = KeyPair::generate(curve).unwrap();
+        let args = payload::verify_msg(&key, "This is some message".to_string()).unwrap();
+        let verify4: bool = call!(
+            &root,
+            contract.verify_msg(args.sign, other.near_public_key(), args.msg, None)
+        )
+        .unwrap_json();
+        assert!(!verify4);
+    }
+}
+
+/// Secret keys as near-cli stores them, from near-api-js' tests.
+#[test]
+fn test_cli_near_cli_keys() {
+    use nearapps_cli::sign;
+    use nearapps_exec::crypto::eddsa_ed25519 as ed;
+
+    let root = init_simulator(None);
+    let contract = setup_exec(&root);
+
+    // ok: round-trips, and the public key is derived from the seed
+    for (seckey, pubkey) in [
+        (
+            "ed25519:5JueXZhEEVqGVT5powZ5twyPP8wrap2K7RdAYGGdjBwiBdd7Hh6aQxMP1u3Ma9Yanq1nEv32EW7u8kUJsZ6f315C",
+            "ed25519:EWrekY1deMND7N3Q7Dixxj12wD7AVjFRt2H9q21QHUSW",
+        ),
+        (
+            "ed25519:2wyRcSwSuHtRVmkMCGjPwnzZmQLeXLzLLyED1NDMt4BjnKgQL6tF85yBx6Jr26D2dUNeC716RBoTxntVHsegogYw",
+            "ed25519:22skMptHjFWNyuEWY22ftn2AbLPSYpmYwGJRGwpNHbTV",
+        ),
+    ] {
+        let key = KeyPair::parse(seckey).unwrap();
+        assert_eq!(key.near_secret_key(), seckey);
+        assert_eq!(key.near_public_key().0, pubkey);
+
+        let extended = key.ed25519_seckey_extended();
+        assert!(extended.is_consistent());
+        assert_eq!(ed::types::SecKeyExtended::from(extended.seed()), extended);
+    }
+
+    // ok: signs as near-api-js does
+    let seckey = near_sdk::bs58::decode(
+        "26x56YPzPDro5t2smQfGcYAPy3j7R2jB2NUb7xKbAGK23B6x4WNQPh3twb6oDksFov5X8ts5CtntUNbpQpAKFdbR",
+    )
+    .into_vec()
+    .unwrap();
+    let seckey = ed::types::SecKeyExtended(seckey.try_into().unwrap());
+    let msg_hash = payload::sha256(b"message");
+    let sign = sign::eddsa_ed25519_sign_extended(seckey.clone(), &msg_hash.0).unwrap();
+    let sign: Bs58EncodedSignature = sign.into();
+    assert_eq!(
+        sign.0,
+        "26gFr4xth7W9K7HPWAxq3BLsua8oTy378mC1MYFiEXHBBpeBjP8WmJEJo8XTBowetvqbRshcQEtBUdwQcAqDyP8T"
+    );
+    let pubkey = NearEncodedPubkey::from(format!(
+        "ed25519:{}",
+        near_sdk::bs58::encode(seckey.pubkey().0).into_string()
+    ));
+    assert_eq!(
+        pubkey.0,
+        "ed25519:AYWv9RAN1hpSQA4p1DLhCNnpnNXwxhfH9qeHN8B4nJ59"
+    );
+    let verified: bool = call!(
+        &root,
+        contract.verify_msg(sign, pubkey, "message".to_string(), None)
+    )
+    .unwrap_json();
+    assert!(verified);
+
+    // fail: the public key doesn't match the seed
+    let mut inconsistent = seckey;
+    inconsistent.0[63] ^= 1;
+    assert!(!inconsistent.is_consistent());
+    let encoded = format!(
+        "ed25519:{}",
+        near_sdk::bs58::encode(inconsistent.0.to_vec()).into_string()
+    );
+    assert!(KeyPair::parse(&encoded).is_err());
+}
+
+/// The tagged, Ethereum and envelope payloads are accepted by the
+/// contract.
+#[test]
+fn test_cli_signed_payloads() {
+    use nearapps_cli::sign;
+    use nearapps_exec::crypto::algorithm::Algorithm;
+    use nearapps_exec::crypto::envelope::{ChainId, Envelope};
+    use nearapps_exec::crypto::nep413::Nonce;
+
+    let root = init_simulator(None);
+    // the chain is from the top-level account of the executor
+    let testnet = root.create_user("testnet".parse().unwrap(), 400 * utils::YOTTA);
+    let contract = utils::setup_exec_at(&root, &testnet, "executor.testnet");
+
+    let msg = "This is some message".to_string();
+    let ed_key = KeyPair::from_seckey(Curve::Ed25519, [1; 32]).unwrap();
+    let secp_key = KeyPair::from_seckey(Curve::Secp256k1, [2; 32]).unwrap();
+
+    // ok: every algorithm
+    for (key, algorithm) in [
+        (&ed_key, Algorithm::Ed25519),
+        (&ed_key, Algorithm::Ed25519ph { context: None }),
+        (
+            &ed_key,
+            Algorithm::Ed25519ctx {
+                context: "app".to_string(),
+            },
+        ),
+        (&secp_key, Algorithm::EcdsaSecp256k1),
+        (&secp_key, Algorithm::EcdsaSecp256k1Recoverable),
+    ] {
+        let args = payload::verify_tagged_msg(key, algorithm.clone(), msg.clone()).unwrap();
+        let verified: bool = call!(
+            &root,
+            contract.verify_tagged_msg(args.algorithm, args.sign, args.pubkey, args.msg)
+        )
+        .unwrap_json();
+        assert!(verified, "{:?}", algorithm);
+    }
+
+    // fail: the algorithm is not for the curve of the key
+    assert!(payload::verify_tagged_msg(&secp_key, Algorithm::Ed25519, msg.clone()).is_err());
+
+    // fail: a context that is too long for Ed25519ctx
+    let seckey = ed_key.ed25519_seckey();
+    assert!(sign::eddsa_ed25519_sign_ctx(seckey, msg.as_bytes(), &"a".repeat(256)).is_err());
+
+    // ok: signed with `personal_sign`, as web3 does for its key
+    let web3_key = KeyPair::from_seckey(
+        Curve::Secp256k1,
+        utils::hex("0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318")
+            .try_into()
+            .unwrap(),
+    )
+    .unwrap();
+    let args = payload::verify_by_eth_address(&web3_key, "Some data".to_string()).unwrap();
+    assert_eq!(args.address.0, "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23");
+    let verified: bool = call!(
+        &root,
+        contract.verify_by_eth_address(args.address, args.sign, args.msg)
+    )
+    .unwrap_json();
+    assert!(verified);
+
+    // fail: not a secp256k1 key
+    assert!(payload::verify_by_eth_address(&ed_key, msg).is_err());
+
+    // ok: an envelope for the executor
+    let now = root.borrow_runtime().current_block().block_timestamp;
+    let envelope = Envelope {
+        domain: "app.example.com".to_string(),
+        contract_id: contract.account_id(),
+        chain_id: ChainId::Testnet,
+        nonce: Nonce([7; 32]),
+        issued_at: now.into(),
+        expires_at: (now + 3_600_000_000_000).into(),
+        payload: "transfer 1 NEAR to bob.near".to_string(),
+    };
+    let args = payload::verify_envelope(&secp_key, envelope).unwrap();
+    let verified: bool = call!(
+        &root,
+        contract.verify_envelope(args.envelope, args.sign, args.pubkey, args.domain)
+    )
+    .unwrap_json();
+    assert!(verified);
+}
@@ -0,0 +1,118 @@
+use crate::utils::hex;
+use near_sdk::serde_json::{self, Value};
+use nearapps_cli::merkle::MerkleTree;
+use nearapps_cli::payload;
+use nearapps_exec::hash::merkle::{self, MerkleHash, MerkleNode, Pairing};
+use std::convert::TryInto;
+
+mod utils;
+
+/// A tree in OpenZeppelin's `StandardMerkleTree` of
+/// `["address", "uint256"]` values, with the leaves in the tree order
+/// and the proofs as `getProof()` returns them.
+const OPENZEPPELIN_TREE: &str =
+    include_str!("../../app-exec/tests/data/openzeppelin_merkle_tree.json");
+
+fn node(s: &str) -> MerkleNode {
+    MerkleNode(hex(s).try_into().unwrap())
+}
+
+/// The leaf that OpenZeppelin's `StandardMerkleTree` has for an
+/// `["address", "uint256"]` value, which is the `keccak256` of the
+/// `keccak256` of the abi encoded value.
+fn openzeppelin_leaf(address: &str, amount: &str) -> MerkleNode {
+    let mut encoded = vec![0; 12];
+    encoded.extend(hex(address));
+    encoded.extend(&[0; 16]);
+    encoded.extend(&amount.parse::<u128>().unwrap().to_be_bytes());
+    let keccak = MerkleHash::Keccak256;
+    keccak.hash_leaf(&keccak.hash_leaf(&encoded).0)
+}
+
+/// The trees are built as OpenZeppelin's `StandardMerkleTree`.
+#[test]
+fn test_merkle_openzeppelin() {
+    // ok: the root from OpenZeppelin's `StandardMerkleTree` readme
+    let mut leaves = vec![
+        openzeppelin_leaf(
+            "0x1111111111111111111111111111111111111111",
+            "5000000000000000000",
+        ),
+        openzeppelin_leaf(
+            "0x2222222222222222222222222222222222222222",
+            "2500000000000000000",
+        ),
+    ];
+    leaves.sort();
+    let tree = MerkleTree::sorted(MerkleHash::Keccak256, leaves).unwrap();
+    assert_eq!(
+        tree.root(),
+        node("0xd4dee0beab2d53f2cc83e567171bd2820e49898130a22622b10ead383e90bd77")
+    );
+
+    // ok: the same leaves, root and proofs as OpenZeppelin's
+    let data: Value = serde_json::from_str(OPENZEPPELIN_TREE).unwrap();
+    let entries = data["entries"].as_array().unwrap();
+    let leaves: Vec<MerkleNode> = entries
+        .iter()
+        .map(|entry| {
+            let leaf = node(entry["leaf"].as_str().unwrap());
+            let value = &entry["value"];
+            let derived = openzeppelin_leaf(value[0].as_str().unwrap(), value[1].as_str().unwrap());
+            assert_eq!(derived, leaf);
+            leaf
+        })
+        .collect();
+    let tree = MerkleTree::sorted(MerkleHash::Keccak256, leaves).unwrap();
+    assert_eq!(tree.root(), node(data["root"].as_str().unwrap()));
+
+    for (index, entry) in entries.iter().enumerate() {
+        let siblings: Vec<MerkleNode> = entry["proof"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|sibling| node(sibling.as_str().unwrap()))
+            .collect();
+        let args = payload::verify_merkle_proof(&tree, index).unwrap();
+        assert_eq!(args.proof.pairing, Pairing::Sorted);
+        assert_eq!(args.proof.siblings, siblings);
+        assert!(merkle::verify_proof(&args.root, &args.leaf, &args.proof));
+    }
+}
+
+/// The trees of indexed pairs are padded up to a power of two.
+#[test]
+fn test_merkle_indexed() {
+    let sha256 = MerkleHash::Sha256;
+    let leaves: Vec<MerkleNode> = ["a", "b", "c", "d", "e"]
+        .iter()
+        .map(|leaf| sha256.hash_leaf(leaf.as_bytes()))
+        .collect();
+    let tree = MerkleTree::indexed(sha256, leaves.clone()).unwrap();
+
+    // ok: the leaves are padded with zeroed nodes up to 8
+    assert_eq!(
+        tree.root(),
+        node("c6cde104e4847b9111f224882d4fb270b5f240f1bd24dda998828dc06303708c")
+    );
+
+    // ok: every leaf, at its index
+    for index in 0..leaves.len() {
+        let args = payload::verify_merkle_proof(&tree, index).unwrap();
+        assert_eq!(args.proof.pairing, Pairing::Indexed(index as u32));
+        assert_eq!(args.proof.siblings.len(), 3);
+        assert!(merkle::verify_proof(&args.root, &args.leaf, &args.proof));
+    }
+
+    // ok: a single leaf is its own root
+    let tree = MerkleTree::indexed(sha256, leaves[..1].to_vec()).unwrap();
+    let args = payload::verify_merkle_proof(&tree, 0).unwrap();
+    assert_eq!(args.root, leaves[0]);
+    assert!(args.proof.siblings.is_empty());
+    assert!(merkle::verify_proof(&args.root, &args.leaf, &args.proof));
+
+    // fail: no leaves, or out of the leaves
+    assert!(MerkleTree::indexed(sha256, vec![]).is_err());
+    assert!(MerkleTree::sorted(sha256, vec![]).is_err());
+    assert!(payload::verify_merkle_proof(&tree, 1).is_none());
+}
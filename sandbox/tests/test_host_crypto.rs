@@ -0,0 +1,109 @@
+use serde_json::json;
+use workspaces::network::Sandbox;
+use workspaces::{Contract, Worker};
+
+/// Built by `build.sh`.
+const EXEC_WASM: &str = "../res/nearapps_exec.wasm";
+
+/// Built by `build.sh`, with the `host_crypto` feature.
+const EXEC_HOST_CRYPTO_WASM: &str = "../res/nearapps_exec_host_crypto.wasm";
+
+const MSG: &str = "This is some message";
+
+async fn setup_exec(worker: &Worker<Sandbox>, wasm: &str) -> anyhow::Result<Contract> {
+    let bytes = std::fs::read(wasm)?;
+    let contract = worker.dev_deploy(&bytes).await?;
+    contract
+        .call("new")
+        .args_json(json!({ "owner_id": contract.id() }))
+        .transact()
+        .await?
+        .into_result()?;
+    Ok(contract)
+}
+
+/// Calls `method` on both contracts, and checks that both return
+/// `expected`, and that the host functions burn less gas.
+async fn compare(
+    contract: &Contract,
+    contract_host: &Contract,
+    method: &str,
+    args: serde_json::Value,
+    expected: bool,
+) -> anyhow::Result<()> {
+    let res = contract
+        .call(method)
+        .args_json(args.clone())
+        .max_gas()
+        .transact()
+        .await?;
+    let res_host = contract_host
+        .call(method)
+        .args_json(args)
+        .max_gas()
+        .transact()
+        .await?;
+    println!(
+        "{} gas burnt: wasm {}, host {}",
+        method, res.total_gas_burnt, res_host.total_gas_burnt
+    );
+    assert!(res_host.total_gas_burnt < res.total_gas_burnt);
+    assert_eq!(res.json::<bool>()?, expected);
+    assert_eq!(res_host.json::<bool>()?, expected);
+    Ok(())
+}
+
+/// Copies `sign` with its first byte changed.
+fn bad_sign(sign: &[u8]) -> Vec<u8> {
+    let mut bad_sign = sign.to_vec();
+    bad_sign[0] = bad_sign[0].wrapping_add(1);
+    bad_sign
+}
+
+/// Compares the gas of verifying inside of wasm against verifying
+/// through the `ecrecover` and `ed25519_verify` host functions, and
+/// checks that both agree on the results.
+#[tokio::test]
+async fn test_host_crypto_gas() -> anyhow::Result<()> {
+    let worker = workspaces::sandbox().await?;
+    let contract = setup_exec(&worker, EXEC_WASM).await?;
+    let contract_host = setup_exec(&worker, EXEC_HOST_CRYPTO_WASM).await?;
+
+    // secp256k1, as signed by nearapps-cli with the secret key
+    // 3b940b5586823dfd02ae3b461bb4336b5ecbaefd6627aa922efc048fec0c881c
+    let pubkey: &[u8] = &[
+        2, 29, 21, 35, 7, 198, 183, 43, 14, 208, 65, 139, 14, 112, 205, 128, 231, 245, 41, 91, 141,
+        134, 245, 114, 45, 63, 82, 19, 251, 210, 57, 79, 54,
+    ];
+    let sign: &[u8] = &[
+        12, 4, 34, 223, 125, 111, 38, 168, 214, 37, 2, 54, 6, 11, 138, 205, 81, 79, 164, 232, 210,
+        96, 255, 60, 50, 195, 170, 212, 182, 180, 112, 55, 110, 15, 90, 39, 225, 78, 71, 173, 50,
+        141, 1, 195, 216, 164, 185, 105, 254, 186, 176, 110, 162, 108, 132, 202, 161, 251, 225,
+        119, 157, 98, 167, 133,
+    ];
+    for (sign, expected) in [(sign.to_vec(), true), (bad_sign(sign), false)] {
+        let args = json!({ "pubkey": pubkey, "sign": sign, "msg": MSG });
+        let method = "ecdsa_secp256k1_verify_compressed_msg";
+        compare(&contract, &contract_host, method, args, expected).await?;
+    }
+
+    // ed25519, as signed by nearapps-cli with the secret key
+    // 3e461ba35cb60b034dea62040b7f4fe4f3bb9649c9894c1655fb9802f12a4836
+    let pubkey: &[u8] = &[
+        130, 39, 155, 15, 62, 76, 188, 63, 124, 122, 26, 251, 233, 253, 225, 220, 14, 41, 166, 120,
+        108, 35, 254, 77, 160, 83, 172, 58, 219, 42, 86, 120,
+    ];
+    let sign: &[u8] = &[
+        103, 185, 32, 105, 195, 55, 233, 72, 219, 194, 93, 38, 4, 155, 158, 118, 76, 248, 52, 80,
+        124, 195, 96, 152, 64, 65, 48, 227, 40, 164, 126, 233, 255, 59, 154, 217, 175, 161, 69, 9,
+        160, 231, 99, 74, 12, 55, 60, 42, 148, 84, 60, 107, 169, 214, 246, 164, 30, 48, 184, 149,
+        209, 110, 118, 14,
+    ];
+    for (sign, expected) in [(sign.to_vec(), true), (bad_sign(sign), false)] {
+        let args = json!({ "pubkey": pubkey, "sign": sign, "msg": MSG });
+        let method = "eddsa_ed25519_verify_msg";
+        compare(&contract, &contract_host, method, args, expected).await?;
+    }
+
+    Ok(())
+}